@@ -0,0 +1,18 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use helix::program::Program;
+
+fn integer_literals(c: &mut Criterion) {
+    let source: Vec<String> = (0..10_000u64)
+        .map(|i| (i.wrapping_mul(0x9E37_79B9_7F4A_7C15) % i64::MAX as u64).to_string())
+        .collect();
+
+    let mut program = Program::new();
+    let key = program.add_source("<bench>".to_string(), source.join(" "));
+
+    c.bench_function("lex 10k integer literals", |b| {
+        b.iter(|| program.tokens(black_box(key)).unwrap())
+    });
+}
+
+criterion_group!(benches, integer_literals);
+criterion_main!(benches);
@@ -22,6 +22,15 @@ pub enum NodeKind {
         rhs: Box<Node>,
     },
 
+    /// A chain of comparisons (`a < b < c`), true when every adjacent pair
+    /// compares true. Each operand is evaluated at most once.
+    ComparisonChain {
+        /// The leftmost operand.
+        first: Box<Node>,
+        /// Each following comparison operator and its right hand side.
+        rest: Vec<(Operator, Node)>,
+    },
+
     /// A unary operation.
     UnaryOp {
         /// The unary operator.
@@ -39,11 +48,84 @@ pub enum NodeKind {
     /// A boolean literal.
     Boolean(bool),
 
+    /// The `null` literal.
+    Null,
+
     /// A string literal.
     String(String),
 
     /// A reference to an identifier
     Identifier(String),
+
+    /// A sequence of statements, evaluating to the value of the last one.
+    Block {
+        /// The statements, in order. Never empty.
+        statements: Vec<Node>,
+    },
+
+    /// A conditional (`if a { b } else { c }`).
+    If {
+        /// The condition, which must evaluate to a boolean.
+        condition: Box<Node>,
+        /// The branch taken when the condition is true.
+        then_branch: Box<Node>,
+        /// The branch taken when the condition is false, if any.
+        else_branch: Option<Box<Node>>,
+    },
+
+    /// A loop (`while a { b }`), evaluating to the value of the last iteration.
+    While {
+        /// The condition checked before each iteration, which must evaluate to a boolean.
+        condition: Box<Node>,
+        /// The body of the loop.
+        body: Box<Node>,
+    },
+
+    /// Exits the innermost loop.
+    Break,
+
+    /// Skips to the next iteration of the innermost loop.
+    Continue,
+
+    /// Writes a value to the output (`print 1 + 2`), evaluating to that value.
+    Print {
+        /// The value to print.
+        expression: Box<Node>,
+    },
+
+    /// A variable declaration (`let x = 5`).
+    Let {
+        /// The name of the variable.
+        name: String,
+        /// The initial value of the variable.
+        value: Box<Node>,
+    },
+
+    /// A tuple of values (`(1, "a", true)`).
+    Tuple(Vec<Node>),
+
+    /// An array of values (`[1, 2, 3]`).
+    Array(Vec<Node>),
+
+    /// An assignment to an existing variable (`x = 5`).
+    Assign {
+        /// The name of the variable.
+        name: String,
+        /// The new value of the variable.
+        value: Box<Node>,
+    },
+
+    /// A placeholder for input that failed to parse, only produced by
+    /// [`Parser::parse_resilient`](crate::parser::Parser::parse_resilient).
+    Error,
+
+    /// An index into a value (`t[0]`).
+    Index {
+        /// The value being indexed.
+        target: Box<Node>,
+        /// The index.
+        index: Box<Node>,
+    },
 }
 
 impl Node {
@@ -51,4 +133,219 @@ impl Node {
     pub fn new(kind: NodeKind, span: Span) -> Self {
         Self { kind, span }
     }
+
+    /// Compares two trees by kind and structure, ignoring their spans.
+    pub fn structural_eq_ignoring_spans(&self, other: &Node) -> bool {
+        use NodeKind as NK;
+
+        match (&self.kind, &other.kind) {
+            (
+                NK::BinaryOp { lhs, operator, rhs },
+                NK::BinaryOp {
+                    lhs: other_lhs,
+                    operator: other_operator,
+                    rhs: other_rhs,
+                },
+            ) => {
+                operator == other_operator
+                    && lhs.structural_eq_ignoring_spans(other_lhs)
+                    && rhs.structural_eq_ignoring_spans(other_rhs)
+            }
+
+            (
+                NK::ComparisonChain { first, rest },
+                NK::ComparisonChain {
+                    first: other_first,
+                    rest: other_rest,
+                },
+            ) => {
+                first.structural_eq_ignoring_spans(other_first)
+                    && rest.len() == other_rest.len()
+                    && rest
+                        .iter()
+                        .zip(other_rest)
+                        .all(|((op, node), (other_op, other))| {
+                            op == other_op && node.structural_eq_ignoring_spans(other)
+                        })
+            }
+
+            (
+                NK::UnaryOp { operator, operand },
+                NK::UnaryOp {
+                    operator: other_operator,
+                    operand: other_operand,
+                },
+            ) => operator == other_operator && operand.structural_eq_ignoring_spans(other_operand),
+
+            (NK::Tuple(items), NK::Tuple(other_items))
+            | (NK::Array(items), NK::Array(other_items)) => {
+                items.len() == other_items.len()
+                    && items
+                        .iter()
+                        .zip(other_items)
+                        .all(|(item, other)| item.structural_eq_ignoring_spans(other))
+            }
+
+            (
+                NK::Index { target, index },
+                NK::Index {
+                    target: other_target,
+                    index: other_index,
+                },
+            ) => {
+                target.structural_eq_ignoring_spans(other_target)
+                    && index.structural_eq_ignoring_spans(other_index)
+            }
+
+            (NK::Print { expression }, NK::Print { expression: other }) => {
+                expression.structural_eq_ignoring_spans(other)
+            }
+
+            (
+                NK::Let { name, value },
+                NK::Let {
+                    name: other_name,
+                    value: other_value,
+                },
+            ) => name == other_name && value.structural_eq_ignoring_spans(other_value),
+
+            (
+                NK::Assign { name, value },
+                NK::Assign {
+                    name: other_name,
+                    value: other_value,
+                },
+            ) => name == other_name && value.structural_eq_ignoring_spans(other_value),
+
+            (NK::Integer(_), NK::Integer(_))
+            | (NK::Float(_), NK::Float(_))
+            | (NK::Boolean(_), NK::Boolean(_))
+            | (NK::String(_), NK::String(_))
+            | (NK::Identifier(_), NK::Identifier(_)) => self.kind == other.kind,
+
+            (NK::Error, NK::Error)
+            | (NK::Null, NK::Null)
+            | (NK::Break, NK::Break)
+            | (NK::Continue, NK::Continue) => true,
+
+            (
+                NK::While { condition, body },
+                NK::While {
+                    condition: other_condition,
+                    body: other_body,
+                },
+            ) => {
+                condition.structural_eq_ignoring_spans(other_condition)
+                    && body.structural_eq_ignoring_spans(other_body)
+            }
+
+            (
+                NK::If {
+                    condition,
+                    then_branch,
+                    else_branch,
+                },
+                NK::If {
+                    condition: other_condition,
+                    then_branch: other_then,
+                    else_branch: other_else,
+                },
+            ) => {
+                condition.structural_eq_ignoring_spans(other_condition)
+                    && then_branch.structural_eq_ignoring_spans(other_then)
+                    && match (else_branch, other_else) {
+                        (Some(branch), Some(other)) => branch.structural_eq_ignoring_spans(other),
+                        (None, None) => true,
+                        _ => false,
+                    }
+            }
+
+            (
+                NK::Block { statements },
+                NK::Block {
+                    statements: other_statements,
+                },
+            ) => {
+                statements.len() == other_statements.len()
+                    && statements
+                        .iter()
+                        .zip(other_statements)
+                        .all(|(statement, other)| statement.structural_eq_ignoring_spans(other))
+            }
+
+            _ => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use slotmap::{DefaultKey, Key};
+
+    use super::*;
+
+    fn node(kind: NodeKind, start: usize, end: usize) -> Node {
+        Node::new(kind, Span::new(start..end, DefaultKey::null()))
+    }
+
+    fn binary(lhs: Node, operator: Operator, rhs: Node, start: usize, end: usize) -> Node {
+        node(
+            NodeKind::BinaryOp {
+                lhs: Box::new(lhs),
+                operator,
+                rhs: Box::new(rhs),
+            },
+            start,
+            end,
+        )
+    }
+
+    #[test]
+    fn test_structural_eq_ignores_spans() {
+        // `1 + 2` and `1+2`
+        let spaced = binary(
+            node(NodeKind::Integer(1), 0, 1),
+            Operator::Plus,
+            node(NodeKind::Integer(2), 4, 5),
+            0,
+            5,
+        );
+        let compact = binary(
+            node(NodeKind::Integer(1), 0, 1),
+            Operator::Plus,
+            node(NodeKind::Integer(2), 2, 3),
+            0,
+            3,
+        );
+
+        assert_ne!(spaced, compact);
+        assert!(spaced.structural_eq_ignoring_spans(&compact));
+    }
+
+    #[test]
+    fn test_structural_eq_detects_differences() {
+        let lhs = binary(
+            node(NodeKind::Integer(1), 0, 1),
+            Operator::Plus,
+            node(NodeKind::Integer(2), 4, 5),
+            0,
+            5,
+        );
+        let rhs = binary(
+            node(NodeKind::Integer(1), 0, 1),
+            Operator::Minus,
+            node(NodeKind::Integer(2), 4, 5),
+            0,
+            5,
+        );
+
+        assert!(!lhs.structural_eq_ignoring_spans(&rhs));
+        assert!(
+            !node(NodeKind::Integer(1), 0, 1).structural_eq_ignoring_spans(&node(
+                NodeKind::Float(1.0),
+                0,
+                1
+            ))
+        );
+    }
 }
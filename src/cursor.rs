@@ -1,11 +1,27 @@
-use std::iter::Peekable;
+use std::collections::VecDeque;
+
+/// An item whose size determines how far a [`Cursor`] moves past it.
+pub trait Width {
+    /// The number of positions this item takes up.
+    fn width(&self) -> usize;
+}
+
+/// Characters are measured in bytes, so positions can be used to slice the source.
+impl Width for char {
+    fn width(&self) -> usize {
+        self.len_utf8()
+    }
+}
 
 /// A cursor that keeps track of the current item and position over some sequence.
 pub struct Cursor<I: Iterator> {
     /// The thing beeing iterated over.
-    iter: Peekable<I>,
+    iter: I,
+    /// Items that have been peeked at but not yet advanced past.
+    lookahead: VecDeque<I::Item>,
 
-    /// The current index of the iterator.
+    /// The current position in the sequence, the sum of the [`Width`]s of
+    /// every item advanced past.
     pub pos: usize,
     /// The current element of the iterator.
     pub current: Option<I::Item>,
@@ -13,11 +29,12 @@ pub struct Cursor<I: Iterator> {
 
 impl<I: Iterator> Cursor<I>
 where
-    I::Item: Clone,
+    I::Item: Clone + Width,
 {
     pub fn new(iter: I) -> Self {
         Self {
-            iter: iter.peekable(),
+            iter,
+            lookahead: VecDeque::new(),
             pos: 0,
             current: None,
         }
@@ -25,14 +42,25 @@ where
 
     /// Peek at the next character in the input string.
     pub fn peek(&mut self) -> Option<&I::Item> {
-        self.iter.peek()
+        self.peek_nth(0)
+    }
+
+    /// Peek `n` items past the next one, without advancing, so `peek_nth(0)` is the
+    /// same as [`Cursor::peek`].
+    pub fn peek_nth(&mut self, n: usize) -> Option<&I::Item> {
+        while self.lookahead.len() <= n {
+            let item = self.iter.next()?;
+            self.lookahead.push_back(item);
+        }
+
+        self.lookahead.get(n)
     }
 
     /// Advance the cursor by one character.
     pub fn advance(&mut self) -> Option<I::Item> {
-        let value = self.iter.next();
+        let value = self.lookahead.pop_front().or_else(|| self.iter.next());
 
-        self.pos += value.as_ref().map_or(0, |_| 1);
+        self.pos += value.as_ref().map_or(0, Width::width);
         self.current = value.clone();
 
         value
@@ -57,8 +85,27 @@ where
     fn clone(&self) -> Self {
         Self {
             iter: self.iter.clone(),
+            lookahead: self.lookahead.clone(),
             pos: self.pos,
             current: self.current.clone(),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_peek_nth() {
+        let mut cursor = Cursor::new("ab.c".chars());
+
+        assert_eq!(cursor.peek_nth(2), Some(&'.'));
+        assert_eq!(cursor.peek(), Some(&'a'));
+        assert_eq!(cursor.peek_nth(4), None);
+
+        assert_eq!(cursor.advance(), Some('a'));
+        assert_eq!(cursor.peek_nth(1), Some(&'.'));
+        assert_eq!(cursor.pos, 1);
+    }
+}
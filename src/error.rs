@@ -1,8 +1,9 @@
 use thiserror::Error;
 
 use crate::{
+    interpreter::Capability,
     token::{Operator, Span, Token},
-    value::ValueKind,
+    value::{ValueKind, ValueType},
 };
 
 /// An wrapper over Result to be specific to Helix errors
@@ -30,10 +31,20 @@ pub enum ErrorKind {
 pub enum LexerError {
     #[error("encountered an unknown symbol '{0}'")]
     UnknownSymbol(String),
-    #[error("encountered a malformed number '{0}'")]
+    #[error("malformed number '{0}': numbers may contain at most one decimal point")]
     MalformedNumber(String),
     #[error("encountered an unterminated string literal")]
     UnterminatedString,
+    #[error("the number '{0}' has a decimal point, so it cannot have an integer suffix")]
+    InvalidLiteralSuffix(String),
+    #[error("the integer literal '{0}' is too large to fit in an integer")]
+    IntegerLiteralTooLarge(String),
+    #[error("unknown escape sequence '\\{0}'")]
+    InvalidEscape(char),
+    #[error("invalid unicode escape: expected 1 to 6 hex digits of a valid code point in braces")]
+    InvalidUnicodeEscape,
+    #[error("encountered an unterminated block comment")]
+    UnterminatedComment,
 }
 
 /// An error that occurred during the generation of the AST.
@@ -54,15 +65,79 @@ pub enum ParserError {
 /// An error that occured during the runtime of the program.
 #[derive(Error, Debug, Clone)]
 pub enum RuntimeError {
-    #[error("cannot apply binary operator '{operator}' between values of kind {} and {}", lhs.name(), rhs.name())]
+    #[error("{}", invalid_binary_operation(lhs, operator, rhs))]
     InvalidBinaryOperation {
         lhs: ValueKind,
         operator: Operator,
         rhs: ValueKind,
     },
-    #[error("cannot apply unary operator '{operator}' to a value of kind {}", operand.name())]
+    #[error("{}", invalid_unary_operation(operand, operator))]
     InvalidUnaryOperation {
         operand: ValueKind,
         operator: Operator,
     },
+    #[error("expected a value of kind {expected}, found {}", found.name())]
+    UnexpectedValueKind {
+        expected: &'static str,
+        found: ValueKind,
+    },
+    #[error("integer overflow")]
+    IntegerOverflow,
+    #[error("attempted to divide an integer by zero")]
+    DivisionByZero,
+    #[error("{operation} is not defined for {value}")]
+    DomainError {
+        operation: &'static str,
+        value: ValueKind,
+    },
+    #[error("a host function panicked: {0}")]
+    HostFunctionPanicked(String),
+    #[error("the {0} capability has been disabled")]
+    CapabilityDenied(Capability),
+    #[error("failed to write output: {0}")]
+    OutputFailed(String),
+    #[error("exceeded the maximum number of instructions")]
+    InstructionLimitExceeded,
+    #[error("`break` and `continue` can only be used inside a loop")]
+    BreakOutsideLoop,
+    #[error("the variable '{name}' is not defined")]
+    UndefinedVariable { name: String },
+    #[error("cannot index with a value of kind {}; indices must be integers", found.name())]
+    InvalidIndex { found: ValueKind },
+    #[error("index {index} is out of bounds for a {kind} of length {len}")]
+    IndexOutOfBounds {
+        index: i64,
+        len: usize,
+        kind: &'static str,
+    },
+}
+
+fn invalid_binary_operation(lhs: &ValueKind, operator: &Operator, rhs: &ValueKind) -> String {
+    use ValueType as VT;
+
+    let message = format!(
+        "cannot apply binary operator '{operator}' between values of kind {} and {}",
+        lhs.name(),
+        rhs.name()
+    );
+
+    match (lhs.discriminant(), operator, rhs.discriminant()) {
+        (VT::String, Operator::Plus, VT::Integer | VT::Float)
+        | (VT::Integer | VT::Float, Operator::Plus, VT::String) => {
+            format!("{message}; enable the `implicit_string_coercion` option to concatenate numbers with strings")
+        }
+        _ => message,
+    }
+}
+
+fn invalid_unary_operation(operand: &ValueKind, operator: &Operator) -> String {
+    match (operand.discriminant(), operator) {
+        (ValueType::Boolean, Operator::Minus) => {
+            "cannot negate a boolean; did you mean `!`?".to_string()
+        }
+        _ => format!(
+            "cannot apply unary operator '{operator}' to a value of kind {}",
+            operand.name()
+        ),
+    }
 }
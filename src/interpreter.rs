@@ -1,76 +1,425 @@
+use std::{
+    collections::HashMap,
+    fmt::Display,
+    io::{self, Write},
+};
+
 use crate::{
     ast::*,
-    error::Result,
-    token::{ASTNode, Operator, UnaryOperator},
-    value::{Value, ValueKind},
+    error::{Error, Result, RuntimeError},
+    token::{ASTNode, Operator, Span, UnaryOperator},
+    value::{ArithmeticMode, OperatorRegistry, Value, ValueKind},
 };
 
 use NodeKind as NK;
 
-/// Excecutes a source file, and holds information about the current excecution context.
+/// The set of things a script is permitted to do, allowing embedders to sandbox
+/// untrusted code.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Capabilities {
+    /// Whether the script may read from and write to the filesystem.
+    pub filesystem: bool,
+    /// Whether the script may write to standard output.
+    pub stdout: bool,
+    /// Whether the script may read from standard input.
+    pub stdin: bool,
+    /// Whether the script may read the clock or generate random numbers.
+    pub time: bool,
+    /// The maximum number of nodes that may be evaluated in a single run, if any.
+    pub max_instructions: Option<usize>,
+}
+
+/// A single capability that can be denied to a script.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Capability {
+    /// Reading from and writing to the filesystem.
+    Filesystem,
+    /// Writing to standard output.
+    Stdout,
+    /// Reading from standard input.
+    Stdin,
+    /// Reading the clock or generating random numbers.
+    Time,
+}
+
+/// Why evaluation of a node stopped early.
 #[derive(Debug)]
-pub struct Interpreter {}
+enum Unwind {
+    /// A runtime error occurred.
+    Error(Error),
+    /// A `break` is unwinding to the innermost loop.
+    Break(Span),
+    /// A `continue` is unwinding to the innermost loop.
+    Continue(Span),
+}
+
+/// The result of evaluating a node, which may unwind to an enclosing loop.
+type Flow<T> = std::result::Result<T, Unwind>;
+
+impl From<Error> for Unwind {
+    fn from(error: Error) -> Self {
+        Self::Error(error)
+    }
+}
+
+/// Excecutes a source file, and holds information about the current excecution context.
+pub struct Interpreter {
+    /// How integer overflow is handled in arithmetic.
+    pub arithmetic_mode: ArithmeticMode,
+    /// Additional binary operator handlers, consulted before the built in operators.
+    pub operators: OperatorRegistry,
+    /// What the running script is permitted to do.
+    pub capabilities: Capabilities,
+    /// Whether `+` between a string and a number concatenates the number's text.
+    pub implicit_string_coercion: bool,
+    /// Where `print` writes to, standard output by default.
+    pub output: Box<dyn Write>,
+
+    /// How many more nodes may be evaluated before the run is aborted.
+    remaining_instructions: Option<usize>,
+    /// The values of all declared variables, kept between runs.
+    environment: HashMap<String, Value>,
+}
 
 impl Interpreter {
     /// Creates a new interpreter.
     pub fn new() -> Self {
-        Self {}
+        Self {
+            arithmetic_mode: ArithmeticMode::default(),
+            operators: OperatorRegistry::default(),
+            capabilities: Capabilities::default(),
+            implicit_string_coercion: false,
+            output: Box::new(io::stdout()),
+            remaining_instructions: None,
+            environment: HashMap::new(),
+        }
+    }
+
+    /// Discards every declared variable.
+    pub fn reset(&mut self) {
+        self.environment.clear();
+    }
+
+    /// Returns the names of every declared variable.
+    pub(crate) fn variables(&self) -> impl Iterator<Item = &str> {
+        self.environment.keys().map(String::as_str)
     }
 
     /// Starts running the interpreter on the given AST.
     pub fn run(&mut self, ast: ASTNode) -> Result<Value> {
-        self.visit(ast)
+        self.remaining_instructions = self.capabilities.max_instructions;
+
+        self.visit(ast).map_err(|unwind| match unwind {
+            Unwind::Error(error) => error,
+            Unwind::Break(span) | Unwind::Continue(span) => Error {
+                span,
+                kind: RuntimeError::BreakOutsideLoop.into(),
+            },
+        })
     }
 
-    fn visit(&mut self, node: ASTNode) -> Result<Value> {
+    fn visit(&mut self, node: ASTNode) -> Flow<Value> {
+        if let Some(remaining) = &mut self.remaining_instructions {
+            if *remaining == 0 {
+                return Err(Error {
+                    span: node.span,
+                    kind: RuntimeError::InstructionLimitExceeded.into(),
+                }
+                .into());
+            }
+
+            *remaining -= 1;
+        }
+
         match node.kind {
-            NK::Integer(_) | NK::Float(_) | NK::Boolean(_) | NK::String(_) => {
+            NK::Integer(_) | NK::Float(_) | NK::Boolean(_) | NK::String(_) | NK::Null => {
                 Ok(self.construct_literal(node))
             }
 
             NK::BinaryOp { lhs, operator, rhs } => self.visit_binary_op(*lhs, operator, *rhs),
+            NK::ComparisonChain { first, rest } => {
+                self.visit_comparison_chain(*first, rest, node.span)
+            }
             NK::UnaryOp { operator, operand } => self.visit_unary_op(operator, *operand),
-            NK::Identifier(_) => todo!(),
+            NK::Tuple(items) => {
+                let items = items
+                    .into_iter()
+                    .map(|item| self.visit(item))
+                    .collect::<Flow<_>>()?;
+
+                Ok(Value::new(ValueKind::Tuple(items), node.span))
+            }
+            NK::Array(items) => {
+                let items = items
+                    .into_iter()
+                    .map(|item| self.visit(item))
+                    .collect::<Flow<_>>()?;
+
+                Ok(Value::new(ValueKind::Array(items), node.span))
+            }
+            NK::Break => Err(Unwind::Break(node.span)),
+            NK::Continue => Err(Unwind::Continue(node.span)),
+            NK::Error => panic!("attempted to run a tree containing parse errors"),
+            NK::Index { target, index } => self.visit_index(*target, *index, node.span),
+            NK::Block { statements } => {
+                let mut last = None;
+
+                for statement in statements {
+                    last = Some(self.visit(statement)?);
+                }
+
+                Ok(last.expect("blocks should contain at least one statement"))
+            }
+            NK::If {
+                condition,
+                then_branch,
+                else_branch,
+            } => self.visit_if(*condition, *then_branch, else_branch.map(|b| *b), node.span),
+            NK::While { condition, body } => self.visit_while(*condition, *body, node.span),
+            NK::Print { expression } => {
+                self.capabilities.require(Capability::Stdout, node.span)?;
+
+                let value = self.visit(*expression)?;
+
+                writeln!(self.output, "{value}").map_err(|error| Error {
+                    span: node.span,
+                    kind: RuntimeError::OutputFailed(error.to_string()).into(),
+                })?;
+
+                Ok(Value::new(ValueKind::Null, node.span))
+            }
+            NK::Let { name, value } => {
+                let value = self.visit(*value)?;
+                self.environment.insert(name, value);
+
+                Ok(Value::new(ValueKind::Null, node.span))
+            }
+            NK::Assign { name, value } => {
+                if !self.environment.contains_key(&name) {
+                    return Err(Error {
+                        span: node.span,
+                        kind: RuntimeError::UndefinedVariable { name }.into(),
+                    }
+                    .into());
+                }
+
+                let value = self.visit(*value)?;
+                self.environment.insert(name, value.clone());
+
+                Ok(value)
+            }
+            NK::Identifier(name) => match self.environment.get(&name) {
+                Some(value) => Ok(Value::new(value.kind.clone(), node.span)),
+                None => Err(Error {
+                    span: node.span,
+                    kind: RuntimeError::UndefinedVariable { name }.into(),
+                }
+                .into()),
+            },
         }
     }
 
-    fn visit_binary_op(&mut self, lhs: ASTNode, op: Operator, rhs: ASTNode) -> Result<Value> {
-        use Operator as OP;
+    fn visit_while(&mut self, condition: ASTNode, body: ASTNode, span: Span) -> Flow<Value> {
+        let mut last = Value::new(ValueKind::Null, span);
+
+        while self.visit_condition(condition.clone())? {
+            match self.visit(body.clone()) {
+                Ok(value) => last = value,
+                Err(Unwind::Break(_)) => break,
+                Err(Unwind::Continue(_)) => continue,
+                Err(error) => return Err(error),
+            }
+        }
+
+        Ok(last)
+    }
+
+    /// Evaluates the condition of an `if` or `while`, which must be a boolean.
+    fn visit_condition(&mut self, condition: ASTNode) -> Flow<bool> {
+        let condition = self.visit(condition)?;
+
+        match condition.kind {
+            ValueKind::Boolean(condition) => Ok(condition),
+            found => Err(Error {
+                span: condition.span,
+                kind: RuntimeError::UnexpectedValueKind {
+                    expected: "boolean",
+                    found,
+                }
+                .into(),
+            }
+            .into()),
+        }
+    }
+
+    fn visit_if(
+        &mut self,
+        condition: ASTNode,
+        then_branch: ASTNode,
+        else_branch: Option<ASTNode>,
+        span: Span,
+    ) -> Flow<Value> {
+        let condition = self.visit_condition(condition)?;
+
+        match (condition, else_branch) {
+            (true, _) => self.visit(then_branch),
+            (false, Some(else_branch)) => self.visit(else_branch),
+            (false, None) => Ok(Value::new(ValueKind::Null, span)),
+        }
+    }
+
+    fn visit_index(&mut self, target: ASTNode, index: ASTNode, span: Span) -> Flow<Value> {
+        let target = self.visit(target)?;
+        let index = self.visit(index)?;
+
+        let kind = target.kind.name();
 
+        let (ValueKind::Tuple(items) | ValueKind::Array(items)) = target.kind else {
+            return Err(Error {
+                span: target.span,
+                kind: RuntimeError::UnexpectedValueKind {
+                    expected: "tuple or array",
+                    found: target.kind,
+                }
+                .into(),
+            }
+            .into());
+        };
+
+        let ValueKind::Integer(position) = index.kind else {
+            return Err(Error {
+                span: index.span,
+                kind: RuntimeError::InvalidIndex { found: index.kind }.into(),
+            }
+            .into());
+        };
+
+        let item = usize::try_from(position)
+            .ok()
+            .and_then(|position| items.get(position))
+            .ok_or_else(|| Error {
+                span: index.span,
+                kind: RuntimeError::IndexOutOfBounds {
+                    index: position,
+                    len: items.len(),
+                    kind,
+                }
+                .into(),
+            })?;
+
+        Ok(Value::new(item.kind.clone(), span))
+    }
+
+    fn visit_binary_op(&mut self, lhs: ASTNode, op: Operator, rhs: ASTNode) -> Flow<Value> {
         let lhs = self.visit(lhs)?;
+
+        // the right hand side is not evaluated when the left already decides the result
+        let decided = match (op, &lhs.kind) {
+            (Operator::And, ValueKind::Boolean(false)) => Some(false),
+            (Operator::Or, ValueKind::Boolean(true)) => Some(true),
+            _ => None,
+        };
+
+        if let Some(result) = decided {
+            return Ok(Value::new(
+                ValueKind::Boolean(result),
+                lhs.span.merge(rhs.span),
+            ));
+        }
+
         let rhs = self.visit(rhs)?;
 
-        let operator = match op {
-            OP::Plus => Value::add,
-            OP::Minus => Value::subtract,
-            OP::Multiply => Value::multiply,
-            OP::Divide => Value::divide,
-            OP::Equals => Value::equal,
-            OP::NotEquals => Value::not_equal,
-            OP::LessThan => Value::less_than,
-            OP::LessThanEquals => Value::less_than_or_equal,
-            OP::GreaterThan => Value::greater_than,
-            OP::GreaterThanEquals => Value::greater_than_or_equal,
-            OP::And => Value::and,
-            OP::Or => Value::or,
+        Ok(self.apply_binary_op(&lhs, op, &rhs)?)
+    }
+
+    fn visit_comparison_chain(
+        &mut self,
+        first: ASTNode,
+        rest: Vec<(Operator, ASTNode)>,
+        span: Span,
+    ) -> Flow<Value> {
+        let mut lhs = self.visit(first)?;
+
+        for (op, rhs) in rest {
+            let rhs = self.visit(rhs)?;
+
+            if self.apply_binary_op(&lhs, op, &rhs)?.kind == ValueKind::Boolean(false) {
+                return Ok(Value::new(ValueKind::Boolean(false), span));
+            }
+
+            lhs = rhs;
+        }
+
+        Ok(Value::new(ValueKind::Boolean(true), span))
+    }
+
+    fn apply_binary_op(&self, lhs: &Value, op: Operator, rhs: &Value) -> Result<Value> {
+        use Operator as OP;
+
+        if let Some(result) = self.operators.apply(lhs, op, rhs) {
+            return result;
+        }
+
+        if let Some(result) = self.coerce_to_string(lhs, op, rhs) {
+            return Ok(result);
+        }
+
+        let mode = self.arithmetic_mode;
+
+        match op {
+            OP::Plus => lhs.add(rhs, mode),
+            OP::Minus => lhs.subtract(rhs, mode),
+            OP::Multiply => lhs.multiply(rhs, mode),
+            OP::Divide => lhs.divide(rhs),
+            OP::Modulo => lhs.modulo(rhs),
+            OP::Power => lhs.power(rhs, mode),
+            OP::Equals => lhs.equal(rhs),
+            OP::NotEquals => lhs.not_equal(rhs),
+            OP::LessThan => lhs.less_than(rhs),
+            OP::LessThanEquals => lhs.less_than_or_equal(rhs),
+            OP::GreaterThan => lhs.greater_than(rhs),
+            OP::GreaterThanEquals => lhs.greater_than_or_equal(rhs),
+            OP::And => lhs.and(rhs),
+            OP::Or => lhs.or(rhs),
             OP::Not | OP::Assign => {
                 panic!("operator `{op}` should not have been parsed as a binary operator")
             }
+        }
+    }
+
+    /// Concatenates a string and a number with `+`, when implicit coercion is enabled.
+    fn coerce_to_string(&self, lhs: &Value, op: Operator, rhs: &Value) -> Option<Value> {
+        use ValueKind as VK;
+
+        if !self.implicit_string_coercion || op != Operator::Plus {
+            return None;
+        }
+
+        let concatenated = match (&lhs.kind, &rhs.kind) {
+            (VK::String(s), VK::Integer(_) | VK::Float(_)) => format!("{s}{rhs}"),
+            (VK::Integer(_) | VK::Float(_), VK::String(s)) => format!("{lhs}{s}"),
+            _ => return None,
         };
 
-        operator(&lhs, &rhs)
+        Some(Value::new(
+            VK::String(concatenated),
+            lhs.span.merge(rhs.span),
+        ))
     }
 
-    fn visit_unary_op(&mut self, operator: UnaryOperator, operand: ASTNode) -> Result<Value> {
+    fn visit_unary_op(&mut self, operator: UnaryOperator, operand: ASTNode) -> Flow<Value> {
         use UnaryOperator as UnaryOP;
 
         let operand = self.visit(operand)?;
 
-        match operator {
-            UnaryOP::Not => operand.not(),
-            UnaryOP::Minus => operand.negate(),
-            UnaryOP::Plus => Ok(operand),
-        }
+        let value = match operator {
+            UnaryOP::Not => operand.not()?,
+            UnaryOP::Minus => operand.negate(self.arithmetic_mode)?,
+            UnaryOP::Plus => operand,
+            UnaryOP::Factorial => operand.factorial()?,
+        };
+
+        Ok(value)
     }
 
     fn construct_literal(&mut self, node: ASTNode) -> Value {
@@ -79,6 +428,7 @@ impl Interpreter {
             NK::Float(value) => ValueKind::Float(value),
             NK::Boolean(value) => ValueKind::Boolean(value),
             NK::String(value) => ValueKind::String(value),
+            NK::Null => ValueKind::Null,
             _ => panic!("visit_literal was called on a non literal ast node, {node:?}"),
         };
 
@@ -88,3 +438,594 @@ impl Interpreter {
         }
     }
 }
+
+impl Capabilities {
+    /// Returns whether `capability` has been granted.
+    pub fn allows(&self, capability: Capability) -> bool {
+        match capability {
+            Capability::Filesystem => self.filesystem,
+            Capability::Stdout => self.stdout,
+            Capability::Stdin => self.stdin,
+            Capability::Time => self.time,
+        }
+    }
+
+    /// Ensures `capability` has been granted, reporting the denial at `span` otherwise.
+    pub fn require(&self, capability: Capability, span: Span) -> Result<()> {
+        if self.allows(capability) {
+            return Ok(());
+        }
+
+        Err(Error {
+            span,
+            kind: RuntimeError::CapabilityDenied(capability).into(),
+        })
+    }
+}
+
+impl std::fmt::Debug for Interpreter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Interpreter")
+            .field("arithmetic_mode", &self.arithmetic_mode)
+            .field("operators", &self.operators)
+            .field("capabilities", &self.capabilities)
+            .field("implicit_string_coercion", &self.implicit_string_coercion)
+            .field("remaining_instructions", &self.remaining_instructions)
+            .field("environment", &self.environment)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Default for Capabilities {
+    fn default() -> Self {
+        Self {
+            filesystem: true,
+            stdout: true,
+            stdin: true,
+            time: true,
+            max_instructions: None,
+        }
+    }
+}
+
+impl Display for Capability {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Filesystem => "filesystem",
+            Self::Stdout => "stdout",
+            Self::Stdin => "stdin",
+            Self::Time => "time",
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::RefCell, rc::Rc};
+
+    use slotmap::{DefaultKey, Key};
+
+    use crate::{
+        error::ErrorKind,
+        lexer::Lexer,
+        parser::{Parser, ParserConfig},
+        program::Source,
+    };
+
+    use super::*;
+
+    fn parse(source: &str) -> ASTNode {
+        parse_with_config(source, ParserConfig::default())
+    }
+
+    fn parse_with_config(source: &str, config: ParserConfig) -> ASTNode {
+        let tokens = Lexer::new(
+            DefaultKey::null(),
+            &Source {
+                name: "<test>".to_string(),
+                content: source.to_string(),
+            },
+        )
+        .tokenize()
+        .expect("test case did not tokenize properly");
+
+        Parser::new(tokens)
+            .with_config(config)
+            .parse()
+            .expect("test case did not parse properly")
+    }
+
+    #[test]
+    fn test_registered_binary_operator() {
+        let mut interpreter = Interpreter::new();
+
+        assert!(interpreter.run(parse("\"a\" + 1")).is_err());
+
+        interpreter
+            .operators
+            .register(Operator::Plus, "string", "integer", |lhs, rhs| {
+                Ok(Value::new(
+                    ValueKind::String(format!("{lhs}{rhs}")),
+                    lhs.span.merge(rhs.span),
+                ))
+            });
+
+        let value = interpreter.run(parse("\"a\" + 1")).unwrap();
+
+        assert_eq!(value.kind, ValueKind::String("a1".to_string()));
+    }
+
+    #[test]
+    fn test_panicking_registered_operator() {
+        let mut interpreter = Interpreter::new();
+
+        interpreter
+            .operators
+            .register(Operator::Minus, "string", "string", |_, _| {
+                panic!("not implemented")
+            });
+
+        let error = interpreter.run(parse("\"a\" - \"b\"")).unwrap_err();
+
+        assert!(matches!(
+            error.kind,
+            ErrorKind::Runtime(RuntimeError::HostFunctionPanicked(ref message))
+                if message == "not implemented"
+        ));
+        assert_eq!((error.span.start, error.span.end), (0, 9));
+    }
+
+    #[test]
+    fn test_denied_capability() {
+        let mut capabilities = Capabilities::default();
+        let span = Span::new(0..0, DefaultKey::null());
+
+        assert!(capabilities.require(Capability::Filesystem, span).is_ok());
+
+        capabilities.filesystem = false;
+        let error = capabilities
+            .require(Capability::Filesystem, span)
+            .unwrap_err();
+
+        assert!(matches!(
+            error.kind,
+            ErrorKind::Runtime(RuntimeError::CapabilityDenied(Capability::Filesystem))
+        ));
+    }
+
+    #[test]
+    fn test_instruction_budget() {
+        let mut interpreter = Interpreter::new();
+
+        // `1 + 2 * 3` evaluates five nodes.
+        interpreter.capabilities.max_instructions = Some(5);
+        assert!(interpreter.run(parse("1 + 2 * 3")).is_ok());
+
+        interpreter.capabilities.max_instructions = Some(4);
+        let error = interpreter.run(parse("1 + 2 * 3")).unwrap_err();
+
+        assert!(matches!(
+            error.kind,
+            ErrorKind::Runtime(RuntimeError::InstructionLimitExceeded)
+        ));
+    }
+
+    #[test]
+    fn test_negate_boolean_hint() {
+        let mut interpreter = Interpreter::new();
+
+        let error = interpreter.run(parse("-true")).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "cannot negate a boolean; did you mean `!`?"
+        );
+
+        let value = interpreter.run(parse("!true")).unwrap();
+        assert_eq!(value.kind, ValueKind::Boolean(false));
+    }
+
+    #[test]
+    fn test_factorial() {
+        let mut interpreter = Interpreter::new();
+
+        let value = interpreter.run(parse("5!")).unwrap();
+        assert_eq!(value.kind, ValueKind::Integer(120));
+
+        let value = interpreter.run(parse("0! + 3!")).unwrap();
+        assert_eq!(value.kind, ValueKind::Integer(7));
+
+        let error = interpreter.run(parse("(-1)!")).unwrap_err();
+        assert!(matches!(
+            error.kind,
+            ErrorKind::Runtime(RuntimeError::DomainError { .. })
+        ));
+
+        let error = interpreter.run(parse("21!")).unwrap_err();
+        assert!(matches!(
+            error.kind,
+            ErrorKind::Runtime(RuntimeError::IntegerOverflow)
+        ));
+    }
+
+    #[test]
+    fn test_special_float_literals() {
+        let mut interpreter = Interpreter::new();
+
+        let nan = interpreter.run(parse("nan")).unwrap();
+        assert!(matches!(nan.kind, ValueKind::Float(f) if f.is_nan()));
+        assert_eq!(nan.to_string(), "nan");
+
+        let inf = interpreter.run(parse("inf")).unwrap();
+        assert_eq!(inf.kind, ValueKind::Float(f64::INFINITY));
+        assert_eq!(inf.to_string(), "inf");
+
+        let neg_inf = interpreter.run(parse("-inf")).unwrap();
+        assert_eq!(neg_inf.kind, ValueKind::Float(f64::NEG_INFINITY));
+        assert_eq!(neg_inf.to_string(), "-inf");
+
+        let value = interpreter.run(parse("nan == nan")).unwrap();
+        assert_eq!(value.kind, ValueKind::Boolean(false));
+    }
+
+    #[test]
+    fn test_chained_comparisons() {
+        let mut interpreter = Interpreter::new();
+        let config = ParserConfig {
+            chained_comparisons: true,
+        };
+
+        let value = interpreter
+            .run(parse_with_config("1 < 2 < 3", config))
+            .unwrap();
+        assert_eq!(value.kind, ValueKind::Boolean(true));
+
+        let value = interpreter
+            .run(parse_with_config("3 > 2 > 2", config))
+            .unwrap();
+        assert_eq!(value.kind, ValueKind::Boolean(false));
+
+        // later operands are not evaluated once the chain is false
+        let value = interpreter
+            .run(parse_with_config("2 < 1 < true + 1", config))
+            .unwrap();
+        assert_eq!(value.kind, ValueKind::Boolean(false));
+
+        // without the option, `1 < 2` is compared against `3`
+        assert!(interpreter.run(parse("1 < 2 < 3")).is_err());
+    }
+
+    #[test]
+    fn test_implicit_string_coercion() {
+        let mut interpreter = Interpreter::new();
+
+        let error = interpreter.run(parse(r#""count: " + 5"#)).unwrap_err();
+        assert!(error.to_string().contains("implicit_string_coercion"));
+
+        interpreter.implicit_string_coercion = true;
+
+        let value = interpreter.run(parse(r#""count: " + 5"#)).unwrap();
+        assert_eq!(value.kind, ValueKind::String("count: 5".to_string()));
+
+        let value = interpreter.run(parse(r#"2.5 + "x""#)).unwrap();
+        assert_eq!(value.kind, ValueKind::String("2.5x".to_string()));
+
+        // only `+` is affected
+        assert!(interpreter.run(parse(r#""a" * 2"#)).is_err());
+    }
+
+    #[test]
+    fn test_strings() {
+        let mut interpreter = Interpreter::new();
+
+        let value = interpreter.run(parse(r#""foo" + "bar""#)).unwrap();
+        assert_eq!(value.kind, ValueKind::String("foobar".to_string()));
+
+        let value = interpreter.run(parse(r#""x" == "x""#)).unwrap();
+        assert_eq!(value.kind, ValueKind::Boolean(true));
+    }
+
+    #[test]
+    fn test_tuples() {
+        let mut interpreter = Interpreter::new();
+
+        let value = interpreter.run(parse(r#"(1, "a", true)"#)).unwrap();
+        assert_eq!(value.to_string(), r#"(1, "a", true)"#);
+
+        let value = interpreter.run(parse("(1,)")).unwrap();
+        assert_eq!(value.to_string(), "(1,)");
+
+        // parentheses without a comma only group
+        let value = interpreter.run(parse("(1)")).unwrap();
+        assert_eq!(value.kind, ValueKind::Integer(1));
+
+        let value = interpreter.run(parse(r#"(1, "a", true)[1 + 1]"#)).unwrap();
+        assert_eq!(value.kind, ValueKind::Boolean(true));
+
+        let error = interpreter.run(parse("(1, 2)[2]")).unwrap_err();
+        assert!(matches!(
+            error.kind,
+            ErrorKind::Runtime(RuntimeError::IndexOutOfBounds {
+                index: 2,
+                len: 2,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn test_variables() {
+        let mut interpreter = Interpreter::new();
+
+        let value = interpreter.run(parse("let x = 5")).unwrap();
+        assert_eq!(value.kind, ValueKind::Null);
+
+        let value = interpreter.run(parse("x + 1")).unwrap();
+        assert_eq!(value.kind, ValueKind::Integer(6));
+
+        let error = interpreter.run(parse("y")).unwrap_err();
+        assert!(matches!(
+            error.kind,
+            ErrorKind::Runtime(RuntimeError::UndefinedVariable { name }) if name == "y"
+        ));
+    }
+
+    #[test]
+    fn test_assignment() {
+        let mut interpreter = Interpreter::new();
+
+        interpreter.run(parse("let x = 1")).unwrap();
+
+        let value = interpreter.run(parse("x = x + 1")).unwrap();
+        assert_eq!(value.kind, ValueKind::Integer(2));
+
+        let value = interpreter.run(parse("x")).unwrap();
+        assert_eq!(value.kind, ValueKind::Integer(2));
+
+        // assigning never declares a variable
+        let error = interpreter.run(parse("y = 1")).unwrap_err();
+        assert!(matches!(
+            error.kind,
+            ErrorKind::Runtime(RuntimeError::UndefinedVariable { name }) if name == "y"
+        ));
+        assert!(interpreter.run(parse("y")).is_err());
+    }
+
+    #[test]
+    fn test_statements() {
+        let mut interpreter = Interpreter::new();
+
+        let value = interpreter.run(parse("1 + 1\n2 + 2")).unwrap();
+        assert_eq!(value.kind, ValueKind::Integer(4));
+
+        let value = interpreter
+            .run(parse("\nlet x = 1\n\nx = x + 1\nx * 10\n\n"))
+            .unwrap();
+        assert_eq!(value.kind, ValueKind::Integer(20));
+    }
+
+    #[test]
+    fn test_if() {
+        let mut interpreter = Interpreter::new();
+
+        let value = interpreter.run(parse("if true { 1 } else { 2 }")).unwrap();
+        assert_eq!(value.kind, ValueKind::Integer(1));
+
+        let value = interpreter
+            .run(parse("if 1 > 2 { 1 } else if 2 > 1 { 2 } else { 3 }"))
+            .unwrap();
+        assert_eq!(value.kind, ValueKind::Integer(2));
+
+        let error = interpreter.run(parse("if 1 { 1 }")).unwrap_err();
+        assert!(matches!(
+            error.kind,
+            ErrorKind::Runtime(RuntimeError::UnexpectedValueKind {
+                expected: "boolean",
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn test_while() {
+        let mut interpreter = Interpreter::new();
+
+        let value = interpreter
+            .run(parse(
+                "let i = 0\nlet total = 0\nwhile i < 4 {\n i = i + 1\n total = total + i\n}",
+            ))
+            .unwrap();
+        assert_eq!(value.kind, ValueKind::Integer(10));
+
+        let value = interpreter.run(parse("i")).unwrap();
+        assert_eq!(value.kind, ValueKind::Integer(4));
+
+        let value = interpreter.run(parse("while false { 1 }")).unwrap();
+        assert_eq!(value.kind, ValueKind::Null);
+
+        assert!(interpreter.run(parse("while 1 { 1 }")).is_err());
+    }
+
+    #[test]
+    fn test_break_and_continue() {
+        let mut interpreter = Interpreter::new();
+
+        let source = "let i = 0\nwhile true {\n i = i + 1\n if i == 3 { break }\n}\ni";
+        let value = interpreter.run(parse(source)).unwrap();
+        assert_eq!(value.kind, ValueKind::Integer(3));
+
+        // sums the odd numbers up to 5
+        let source = "let i = 0\nlet total = 0\nwhile i < 5 {\n i = i + 1\n if i / 2 * 2 == i { continue }\n total = total + i\n}\ntotal";
+        let value = interpreter.run(parse(source)).unwrap();
+        assert_eq!(value.kind, ValueKind::Integer(9));
+
+        let error = interpreter.run(parse("1 + break")).unwrap_err();
+        assert!(matches!(
+            error.kind,
+            ErrorKind::Runtime(RuntimeError::BreakOutsideLoop)
+        ));
+    }
+
+    #[test]
+    fn test_short_circuiting() {
+        let mut interpreter = Interpreter::new();
+
+        // `missing` is undefined, so evaluating it would be an error
+        let value = interpreter.run(parse("false && missing")).unwrap();
+        assert_eq!(value.kind, ValueKind::Boolean(false));
+
+        let value = interpreter.run(parse("true || missing")).unwrap();
+        assert_eq!(value.kind, ValueKind::Boolean(true));
+
+        assert!(interpreter.run(parse("true && missing")).is_err());
+
+        let error = interpreter.run(parse("1 || true")).unwrap_err();
+        assert!(matches!(
+            error.kind,
+            ErrorKind::Runtime(RuntimeError::InvalidBinaryOperation { .. })
+        ));
+    }
+
+    /// An output sink that can be read back after the interpreter has written to it.
+    #[derive(Clone, Default)]
+    struct SharedBuffer(Rc<RefCell<Vec<u8>>>);
+
+    impl Write for SharedBuffer {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.borrow_mut().write(buf)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_print() {
+        let buffer = SharedBuffer::default();
+
+        let mut interpreter = Interpreter::new();
+        interpreter.output = Box::new(buffer.clone());
+
+        let value = interpreter.run(parse("print 1 + 2")).unwrap();
+        assert_eq!(value.kind, ValueKind::Null);
+        assert_eq!(buffer.0.borrow().as_slice(), b"3\n");
+
+        interpreter.capabilities.stdout = false;
+
+        let error = interpreter.run(parse("print 4")).unwrap_err();
+        assert!(matches!(
+            error.kind,
+            ErrorKind::Runtime(RuntimeError::CapabilityDenied(Capability::Stdout))
+        ));
+        assert_eq!(buffer.0.borrow().as_slice(), b"3\n");
+    }
+
+    #[test]
+    fn test_modulo() {
+        let mut interpreter = Interpreter::new();
+
+        let value = interpreter.run(parse("7 % 3")).unwrap();
+        assert_eq!(value.kind, ValueKind::Integer(1));
+
+        let value = interpreter.run(parse("7%3")).unwrap();
+        assert_eq!(value.kind, ValueKind::Integer(1));
+
+        let value = interpreter.run(parse("7.5 % 2")).unwrap();
+        assert_eq!(value.kind, ValueKind::Float(1.5));
+
+        let error = interpreter.run(parse("7 % 0")).unwrap_err();
+        assert!(matches!(
+            error.kind,
+            ErrorKind::Runtime(RuntimeError::DivisionByZero)
+        ));
+    }
+
+    #[test]
+    fn test_power() {
+        let mut interpreter = Interpreter::new();
+
+        let value = interpreter.run(parse("2 ** 3 ** 2")).unwrap();
+        assert_eq!(value.kind, ValueKind::Integer(512));
+
+        let value = interpreter.run(parse("2 * 3 ** 2")).unwrap();
+        assert_eq!(value.kind, ValueKind::Integer(18));
+
+        // unary minus applies to the result, as in `-(2 ** 2)`
+        let value = interpreter.run(parse("-2 ** 2")).unwrap();
+        assert_eq!(value.kind, ValueKind::Integer(-4));
+
+        let value = interpreter.run(parse("(-2) ** 2")).unwrap();
+        assert_eq!(value.kind, ValueKind::Integer(4));
+
+        let value = interpreter.run(parse("2 ** -1")).unwrap();
+        assert_eq!(value.kind, ValueKind::Float(0.5));
+
+        interpreter.arithmetic_mode = ArithmeticMode::Wrapping;
+        let value = interpreter.run(parse("2 ** 64")).unwrap();
+        assert_eq!(value.kind, ValueKind::Integer(0));
+    }
+
+    #[test]
+    fn test_leading_decimal_point() {
+        let mut interpreter = Interpreter::new();
+
+        let value = interpreter.run(parse(".5 + .5 == 1.0")).unwrap();
+        assert_eq!(value.kind, ValueKind::Boolean(true));
+    }
+
+    #[test]
+    fn test_null() {
+        let mut interpreter = Interpreter::new();
+
+        let value = interpreter.run(parse("null")).unwrap();
+        assert_eq!(value.kind, ValueKind::Null);
+        assert_eq!(value.to_string(), "null");
+
+        let value = interpreter.run(parse("null == null")).unwrap();
+        assert_eq!(value.kind, ValueKind::Boolean(true));
+
+        let value = interpreter
+            .run(parse("(null == 0, \"\" == null, null != false)"))
+            .unwrap();
+        assert_eq!(value.to_string(), "(false, false, true)");
+
+        let value = interpreter.run(parse("if false { 1 }")).unwrap();
+        assert_eq!(value.kind, ValueKind::Null);
+
+        assert!(interpreter.run(parse("null + 1")).is_err());
+    }
+
+    #[test]
+    fn test_arrays() {
+        let mut interpreter = Interpreter::new();
+
+        let value = interpreter.run(parse(r#"[1, "a", [true]]"#)).unwrap();
+        assert_eq!(value.to_string(), r#"[1, "a", [true]]"#);
+
+        let value = interpreter.run(parse("[]")).unwrap();
+        assert_eq!(value.to_string(), "[]");
+
+        let value = interpreter
+            .run(parse("let xs = [10, 20, 30]\nxs[1] + xs[2]"))
+            .unwrap();
+        assert_eq!(value.kind, ValueKind::Integer(50));
+
+        let error = interpreter.run(parse("xs[-1]")).unwrap_err();
+        assert!(matches!(
+            error.kind,
+            ErrorKind::Runtime(RuntimeError::IndexOutOfBounds {
+                index: -1,
+                len: 3,
+                kind: "array",
+            })
+        ));
+
+        let error = interpreter.run(parse("xs[1.0]")).unwrap_err();
+        assert!(matches!(
+            error.kind,
+            ErrorKind::Runtime(RuntimeError::InvalidIndex {
+                found: ValueKind::Float(_)
+            })
+        ));
+    }
+}
@@ -1,4 +1,4 @@
-use std::str::Chars;
+use std::{borrow::Cow, str::Chars, vec};
 
 use slotmap::DefaultKey;
 use unicode_xid::UnicodeXID;
@@ -10,14 +10,27 @@ use crate::{
     token::*,
 };
 
+/// Options controlling how source code is tokenized.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct LexerConfig {
+    /// Whether `and`, `or` and `not` are lexed as the `&&`, `||` and `!` operators.
+    /// When disabled, they are ordinary identifiers.
+    pub word_operators: bool,
+}
+
 /// Converts a string into a list of tokens.
-pub struct Lexer<'a> {
+pub struct Lexer<'a, I = Chars<'a>>
+where
+    I: Iterator<Item = char>,
+{
     /// The cursor over the source code.
-    cursor: Cursor<Chars<'a>>,
+    cursor: Cursor<I>,
     /// The source file being tokenized.
-    source: &'a Source,
+    source: Cow<'a, Source>,
     /// The key of the source file, used to give tokens spans.
     key: DefaultKey,
+    /// The options used while tokenizing.
+    config: LexerConfig,
 }
 
 impl<'a> Lexer<'a> {
@@ -26,23 +39,64 @@ impl<'a> Lexer<'a> {
         Self {
             cursor: Cursor::new(source.content.chars()),
             key,
-            source,
+            source: Cow::Borrowed(source),
+            config: LexerConfig::default(),
         }
     }
+}
+
+impl Lexer<'static, vec::IntoIter<char>> {
+    /// Creates a new lexer that owns its source, so it is not tied to the
+    /// lifetime of a borrow.
+    pub fn new_owned(key: DefaultKey, source: Source) -> Self {
+        let chars: Vec<char> = source.content.chars().collect();
+
+        Self {
+            cursor: Cursor::new(chars.into_iter()),
+            key,
+            source: Cow::Owned(source),
+            config: LexerConfig::default(),
+        }
+    }
+}
+
+impl<'a, I> Lexer<'a, I>
+where
+    I: Iterator<Item = char> + Clone,
+{
+    /// Sets the options used while tokenizing.
+    pub fn with_config(mut self, config: LexerConfig) -> Self {
+        self.config = config;
+        self
+    }
 
     /// Starts the tokenization process.
     pub fn tokenize(mut self) -> Result<Vec<Token>> {
-        let mut tokens = Vec::new();
+        let mut tokens: Vec<Token> = Vec::new();
+        let mut brackets = Vec::new();
 
         while let Some(token) = self.next()? {
-            match token {
-                Token {
-                    kind: TokenKind::Whitespace,
+            match token.kind {
+                TokenKind::Whitespace => continue,
+
+                TokenKind::Newline if continues_line(&brackets, tokens.last()) => continue,
+
+                TokenKind::Parenthesis(Parenthesis {
+                    kind,
+                    opening: Opening::Open,
+                }) => brackets.push(kind),
+
+                TokenKind::Parenthesis(Parenthesis {
+                    opening: Opening::Close,
                     ..
-                } => continue,
+                }) => {
+                    brackets.pop();
+                }
 
-                token => tokens.push(token),
+                _ => {}
             }
+
+            tokens.push(token);
         }
 
         Ok(tokens)
@@ -53,15 +107,23 @@ impl<'a> Lexer<'a> {
         let start = self.cursor.pos;
 
         let next = match self.cursor.peek() {
-            Some(c) => c,
+            Some(c) => *c,
             None => return Ok(None),
         };
 
         let kind = match next {
+            '\n' => {
+                self.cursor.advance();
+                TokenKind::Newline
+            }
+
             c if c.is_whitespace() => self.skip_whitespace(),
 
             c if c.is_ascii_digit() => self.tokenize_number()?,
 
+            // a `.` not followed by a digit is left for a future field access operator
+            '.' if self.digit_follows() => self.tokenize_number()?,
+
             c if c.is_xid_start() => self.tokenize_identifier(),
 
             '"' => self.tokenize_string()?,
@@ -69,23 +131,38 @@ impl<'a> Lexer<'a> {
             c if c.is_operator_start() => {
                 let next = self.cursor.advance().expect("should have next char");
 
-                let operator = Operator::from_chars(next, self.cursor.peek().copied())
-                    .expect("operator should be valid as first char sequence was valid start");
-
-                if operator.is_two_char() {
+                if next == '/' && self.cursor.peek() == Some(&'*') {
                     self.cursor.advance();
-                }
+                    self.skip_block_comment(start)?
+                } else {
+                    let operator = Operator::from_chars(next, self.cursor.peek().copied())
+                        .expect("operator should be valid as first char sequence was valid start");
 
-                TokenKind::Operator(operator)
+                    if operator.is_two_char() {
+                        self.cursor.advance();
+                    }
+
+                    TokenKind::Operator(operator)
+                }
             }
 
             c if c.is_parenthesis() => {
-                let paren = Parenthesis::from_char(*next).expect("parenthesis should be valid");
+                let paren = Parenthesis::from_char(next).expect("parenthesis should be valid");
                 self.cursor.advance();
 
                 TokenKind::Parenthesis(paren)
             }
 
+            ',' => {
+                self.cursor.advance();
+                TokenKind::Comma
+            }
+
+            ';' => {
+                self.cursor.advance();
+                TokenKind::Semicolon
+            }
+
             _ => {
                 self.cursor.advance_while(|c| !c.is_whitespace());
 
@@ -106,12 +183,53 @@ impl<'a> Lexer<'a> {
         }))
     }
 
-    /// Skips whitespace characters.
+    /// Returns whether the character after the next one is an ASCII digit.
+    fn digit_follows(&mut self) -> bool {
+        matches!(self.cursor.peek_nth(1), Some(c) if c.is_ascii_digit())
+    }
+
+    /// Skips whitespace characters, stopping at line breaks.
     fn skip_whitespace(&mut self) -> TokenKind {
-        self.cursor.advance_while(|c| c.is_whitespace());
+        self.cursor
+            .advance_while(|c| c.is_whitespace() && *c != '\n');
         TokenKind::Whitespace
     }
 
+    /// Skips a `/* ... */` comment whose opening `/*` starts at `start`.
+    /// Comments nest, so code that already contains comments can be commented out.
+    /// A comment spanning several lines still separates the code around it.
+    fn skip_block_comment(&mut self, start: usize) -> Result<TokenKind> {
+        let mut depth = 1;
+        let mut multiline = false;
+
+        while depth > 0 {
+            match self.cursor.advance() {
+                Some('/') if self.cursor.peek() == Some(&'*') => {
+                    self.cursor.advance();
+                    depth += 1;
+                }
+                Some('*') if self.cursor.peek() == Some(&'/') => {
+                    self.cursor.advance();
+                    depth -= 1;
+                }
+                Some('\n') => multiline = true,
+                Some(_) => {}
+                None => {
+                    return Err(Error {
+                        span: Span::new(start..start + 2, self.key),
+                        kind: LexerError::UnterminatedComment.into(),
+                    })
+                }
+            }
+        }
+
+        Ok(if multiline {
+            TokenKind::Newline
+        } else {
+            TokenKind::Whitespace
+        })
+    }
+
     /// Consumes an identifier or keyword if applicable.
     fn tokenize_identifier(&mut self) -> TokenKind {
         let start = self.cursor.pos;
@@ -120,58 +238,217 @@ impl<'a> Lexer<'a> {
 
         let ident = &self.source.content[start..end];
 
-        match Keyword::from_ident(ident) {
-            Some(keyword) => TokenKind::Keyword(keyword),
-            None => TokenKind::Identifier(ident.to_owned()),
+        let Some(keyword) = Keyword::from_ident(ident) else {
+            return TokenKind::Identifier(ident.to_owned());
+        };
+
+        match keyword.as_operator() {
+            Some(operator) if self.config.word_operators => TokenKind::Operator(operator),
+            Some(_) => TokenKind::Identifier(ident.to_owned()),
+            None => TokenKind::Keyword(keyword),
         }
     }
 
-    /// Consumes a floating point literal or an integer literal.
+    /// Consumes a floating point literal, an integer literal, or a percentage.
     fn tokenize_number(&mut self) -> Result<TokenKind> {
         let start = self.cursor.pos;
 
-        let mut dot_count = 0;
+        let mut is_float = false;
+
+        // accumulate the integer part while scanning, so integers don't need a
+        // second pass through `str::parse`; `None` once it no longer fits
+        let mut integer = Some(0i64);
 
-        self.cursor.advance_while(|c| c.is_ascii_digit());
+        while let Some(digit) = self.cursor.peek().and_then(|c| c.to_digit(10)) {
+            self.cursor.advance();
+            integer = integer.and_then(|n| n.checked_mul(10)?.checked_add(digit.into()));
+        }
 
         while let Some('.') = self.cursor.peek() {
             self.cursor.advance();
-            self.cursor.advance_while(|c| c.is_ascii_digit());
 
-            dot_count += 1;
+            // stop at the second decimal point, so the error points at it
+            if is_float {
+                let span = Span::new(start..self.cursor.pos, self.key);
+
+                return Err(Error {
+                    span,
+                    kind: LexerError::MalformedNumber(self.source[span].to_string()).into(),
+                });
+            }
+
+            self.cursor.advance_while(|c| c.is_ascii_digit());
+            is_float = true;
         }
 
+        let suffix = self.number_suffix();
+
         let span = Span::new(start..self.cursor.pos, self.key);
         let range_str = &self.source[span];
-
-        match dot_count {
-            0 => Ok(TokenKind::Integer(range_str.parse().unwrap())),
-            1 => Ok(TokenKind::Float(range_str.parse().unwrap())),
-            _ => Err(Error {
+        let integer = || {
+            integer.ok_or_else(|| Error {
                 span,
-                kind: LexerError::MalformedNumber(self.source[span].to_string()).into(),
-            }),
+                kind: LexerError::IntegerLiteralTooLarge(range_str.to_string()).into(),
+            })
+        };
+
+        // An `i` or `f` suffix forces the literal to be an integer or a float.
+        if let Some(suffix) = suffix {
+            self.cursor.advance();
+
+            return match (suffix, is_float) {
+                ('f', _) => Ok(TokenKind::Float(range_str.parse().unwrap())),
+                (_, false) => integer().map(TokenKind::Integer),
+                (_, true) => {
+                    let span = Span::new(start..self.cursor.pos, self.key);
+
+                    Err(Error {
+                        span,
+                        kind: LexerError::InvalidLiteralSuffix(self.source[span].to_string())
+                            .into(),
+                    })
+                }
+            };
+        }
+
+        // A `%` written directly after a number (`50%`) is a percentage, unless an
+        // operand follows it (`50%3`), in which case it is the remainder operator.
+        let operand_follows = matches!(
+            self.cursor.peek_nth(1),
+            Some(c) if c.is_ascii_digit() || c.is_xid_start() || matches!(c, '(' | '.')
+        );
+
+        if self.cursor.peek() == Some(&'%') && !operand_follows {
+            self.cursor.advance();
+
+            let value: f64 = range_str.parse().unwrap();
+            return Ok(TokenKind::Float(value / 100.0));
+        }
+
+        if is_float {
+            Ok(TokenKind::Float(range_str.parse().unwrap()))
+        } else {
+            integer().map(TokenKind::Integer)
+        }
+    }
+
+    /// Returns the type suffix (`i` or `f`) directly following a number, if any.
+    ///
+    /// The suffix must end the literal, so `2if` is not treated as suffixed.
+    fn number_suffix(&mut self) -> Option<char> {
+        let suffix = self
+            .cursor
+            .peek()
+            .copied()
+            .filter(|c| matches!(c, 'i' | 'f'))?;
+
+        match self.cursor.peek_nth(1) {
+            Some(c) if c.is_xid_continue() => None,
+            _ => Some(suffix),
         }
     }
 
-    /// Consumes a string literal.
+    /// Consumes a string literal, decoding any escape sequences.
     fn tokenize_string(&mut self) -> Result<TokenKind> {
         self.cursor.advance();
 
         let start = self.cursor.pos;
-        self.cursor.advance_while(|c| *c != '"');
-        let span = Span::new(start..self.cursor.pos, self.key);
-
-        self.cursor.advance();
+        let mut contents = String::new();
+
+        loop {
+            match self.cursor.advance() {
+                Some('"') => return Ok(TokenKind::String(contents)),
+                Some('\\') => contents.push(self.tokenize_escape()?),
+                Some(c) => contents.push(c),
+                None => {
+                    return Err(Error {
+                        span: Span::new(start..self.cursor.pos, self.key),
+                        kind: LexerError::UnterminatedString.into(),
+                    })
+                }
+            }
+        }
+    }
 
-        match self.cursor.current {
-            Some('"') => Ok(TokenKind::String(self.source[span].to_string())),
-            _ => Err(Error {
-                span,
+    /// Decodes the escape sequence following a backslash.
+    fn tokenize_escape(&mut self) -> Result<char> {
+        let backslash = self.cursor.pos - 1;
+
+        match self.cursor.advance() {
+            Some('n') => Ok('\n'),
+            Some('t') => Ok('\t'),
+            Some('r') => Ok('\r'),
+            Some('0') => Ok('\0'),
+            Some(c @ ('\\' | '"')) => Ok(c),
+            Some('u') => self.tokenize_unicode_escape(backslash),
+            Some(c) => Err(Error {
+                span: Span::new(backslash..self.cursor.pos, self.key),
+                kind: LexerError::InvalidEscape(c).into(),
+            }),
+            None => Err(Error {
+                span: Span::new(backslash..self.cursor.pos, self.key),
                 kind: LexerError::UnterminatedString.into(),
             }),
         }
     }
+
+    /// Decodes the `{...}` part of a `\u{...}` escape, containing 1-6 hex digits.
+    fn tokenize_unicode_escape(&mut self, backslash: usize) -> Result<char> {
+        let mut digits = String::new();
+        let mut terminated = false;
+
+        if let Some('{') = self.cursor.peek() {
+            self.cursor.advance();
+
+            while let Some(c) = self.cursor.peek().copied() {
+                if c == '"' {
+                    break;
+                }
+
+                self.cursor.advance();
+
+                if c == '}' {
+                    terminated = true;
+                    break;
+                }
+
+                digits.push(c);
+            }
+        }
+
+        // `from_str_radix` alone would also accept a leading `+`
+        let valid = terminated
+            && (1..=6).contains(&digits.len())
+            && digits.chars().all(|c| c.is_ascii_hexdigit());
+
+        u32::from_str_radix(&digits, 16)
+            .ok()
+            .filter(|_| valid)
+            .and_then(char::from_u32)
+            .ok_or_else(|| Error {
+                span: Span::new(backslash..self.cursor.pos, self.key),
+                kind: LexerError::InvalidUnicodeEscape.into(),
+            })
+    }
+}
+
+/// Returns whether a line break is part of the expression it appears in, rather
+/// than a statement separator. That is the case inside `()` and `[]`, and after a
+/// binary operator or comma, so long expressions can be split across lines.
+fn continues_line(brackets: &[ParenthesisKind], previous: Option<&Token>) -> bool {
+    if matches!(
+        brackets.last(),
+        Some(ParenthesisKind::Round | ParenthesisKind::Square)
+    ) {
+        return true;
+    }
+
+    match previous.map(|token| &token.kind) {
+        Some(TokenKind::Comma) => true,
+        // `!` is also the postfix factorial, which can end a line
+        Some(TokenKind::Operator(operator)) => *operator != Operator::Not,
+        _ => false,
+    }
 }
 
 #[cfg(test)]
@@ -183,6 +460,10 @@ mod tests {
     use super::*;
 
     fn tokenize(source: &str) -> Result<Vec<Token>> {
+        tokenize_with_config(source, LexerConfig::default())
+    }
+
+    fn tokenize_with_config(source: &str, config: LexerConfig) -> Result<Vec<Token>> {
         Lexer::new(
             DefaultKey::null(),
             &Source {
@@ -190,15 +471,32 @@ mod tests {
                 content: source.to_string(),
             },
         )
+        .with_config(config)
         .tokenize()
     }
 
     #[test]
     fn test_whitespace() {
-        let source = "  \t\n  ";
+        let source = "  \t\r  ";
         let tokens = tokenize(source).unwrap();
 
         assert_eq!(tokens.len(), 0);
+
+        let kinds: Vec<_> = tokenize("1 \r\n\n 2")
+            .unwrap()
+            .into_iter()
+            .map(|token| token.kind)
+            .collect();
+
+        assert_eq!(
+            kinds,
+            vec![
+                TokenKind::Integer(1),
+                TokenKind::Newline,
+                TokenKind::Newline,
+                TokenKind::Integer(2),
+            ]
+        );
     }
 
     #[test]
@@ -224,13 +522,13 @@ mod tests {
             })
         ));
 
-        assert!(match tokens.next() {
+        assert!(matches!(
+            tokens.next(),
             Some(Token {
                 kind: TokenKind::Float(c),
                 ..
-            }) if (c - 2.222).abs() < f64::EPSILON => true,
-            _ => false,
-        });
+            }) if (c - 2.222).abs() < f64::EPSILON
+        ));
     }
 
     #[test]
@@ -242,6 +540,15 @@ mod tests {
             error.kind,
             ErrorKind::Lexer(LexerError::MalformedNumber(_))
         ));
+
+        let source = "1 + 1.2.3";
+        let error = tokenize(source).unwrap_err();
+
+        assert_eq!(&source[error.span.start..error.span.end], "1.2.");
+        assert_eq!(
+            error.to_string(),
+            "malformed number '1.2.': numbers may contain at most one decimal point"
+        );
     }
 
     #[test]
@@ -308,4 +615,248 @@ mod tests {
             })
         ));
     }
+
+    #[test]
+    fn test_word_operators() {
+        let kinds = |source, word_operators| {
+            tokenize_with_config(source, LexerConfig { word_operators })
+                .unwrap()
+                .into_iter()
+                .map(|token| token.kind)
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            kinds("and or not", true),
+            vec![
+                TokenKind::Operator(Operator::And),
+                TokenKind::Operator(Operator::Or),
+                TokenKind::Operator(Operator::Not),
+            ]
+        );
+
+        assert_eq!(
+            kinds("and", false),
+            vec![TokenKind::Identifier("and".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_percentages() {
+        let tokens = tokenize("50% 2.5%").unwrap();
+
+        assert!(matches!(
+            tokens[0],
+            Token {
+                kind: TokenKind::Float(f),
+                span: Span { start: 0, end: 3, .. },
+            } if (f - 0.5).abs() < f64::EPSILON
+        ));
+        assert!(matches!(
+            tokens[1].kind,
+            TokenKind::Float(f) if (f - 0.025).abs() < f64::EPSILON
+        ));
+
+        // a separated `%`, or one followed by an operand, is the remainder operator
+        let tokens = tokenize("100 % 3").unwrap();
+        assert_eq!(tokens[1].kind, TokenKind::Operator(Operator::Modulo));
+
+        for source in ["7%3", "7%x", "7%(3)"] {
+            let tokens = tokenize(source).unwrap();
+            assert_eq!(tokens[0].kind, TokenKind::Integer(7));
+            assert_eq!(tokens[1].kind, TokenKind::Operator(Operator::Modulo));
+        }
+    }
+
+    #[test]
+    fn test_number_suffixes() {
+        let tokens = tokenize("42f 42i 2.5f 99999999999999999999.5").unwrap();
+
+        assert!(matches!(
+            tokens[0],
+            Token {
+                kind: TokenKind::Float(f),
+                span: Span { start: 0, end: 3, .. },
+            } if (f - 42.0).abs() < f64::EPSILON
+        ));
+        assert_eq!(tokens[1].kind, TokenKind::Integer(42));
+        assert_eq!(tokens[2].kind, TokenKind::Float(2.5));
+        assert_eq!(tokens[3].kind, TokenKind::Float(99999999999999999999.5));
+
+        let error = tokenize("3.5i").unwrap_err();
+
+        assert!(matches!(
+            error.kind,
+            ErrorKind::Lexer(LexerError::InvalidLiteralSuffix(literal)) if literal == "3.5i"
+        ));
+    }
+
+    #[test]
+    fn test_strings() {
+        let tokens = tokenize(r#""hello" """#).unwrap();
+
+        assert_eq!(tokens[0].kind, TokenKind::String("hello".to_string()));
+        assert_eq!((tokens[0].span.start, tokens[0].span.end), (0, 7));
+        assert_eq!(tokens[1].kind, TokenKind::String(String::new()));
+
+        let error = tokenize(r#"1 + "abc"#).unwrap_err();
+
+        assert!(matches!(
+            error.kind,
+            ErrorKind::Lexer(LexerError::UnterminatedString)
+        ));
+    }
+
+    #[test]
+    fn test_string_escapes() {
+        let tokens = tokenize(r#""a\nb" "\t\"\\\0""#).unwrap();
+
+        assert_eq!(tokens[0].kind, TokenKind::String("a\nb".to_string()));
+        assert_eq!((tokens[0].span.start, tokens[0].span.end), (0, 6));
+        assert_eq!(tokens[1].kind, TokenKind::String("\t\"\\\0".to_string()));
+
+        let source = r#""ok \q""#;
+        let error = tokenize(source).unwrap_err();
+
+        assert!(matches!(
+            error.kind,
+            ErrorKind::Lexer(LexerError::InvalidEscape('q'))
+        ));
+        assert_eq!(&source[error.span.start..error.span.end], r"\q");
+    }
+
+    #[test]
+    fn test_unicode_escapes() {
+        let tokens = tokenize(r#""\u{e9}" "\u{1F600}""#).unwrap();
+
+        assert_eq!(tokens[0].kind, TokenKind::String("é".to_string()));
+        assert_eq!(tokens[1].kind, TokenKind::String("😀".to_string()));
+
+        for source in [
+            r#""\u{110000}""#,
+            r#""\u{1234567}""#,
+            r#""\u{}""#,
+            r#""\u{zz}""#,
+            r#""\u41""#,
+            r#""\u{+41}""#,
+        ] {
+            let error = tokenize(source).unwrap_err();
+
+            assert!(matches!(
+                error.kind,
+                ErrorKind::Lexer(LexerError::InvalidUnicodeEscape)
+            ));
+            assert_eq!(error.span.start, 1);
+        }
+    }
+
+    #[test]
+    fn test_owned_lexer() {
+        let source = Source {
+            name: "<test>".to_string(),
+            content: r#"let x = (1, "a\n") [2.5f]"#.to_string(),
+        };
+
+        let borrowed = Lexer::new(DefaultKey::null(), &source).tokenize().unwrap();
+        let owned = Lexer::new_owned(DefaultKey::null(), source)
+            .tokenize()
+            .unwrap();
+
+        let describe =
+            |tokens: Vec<Token>| -> Vec<_> { tokens.iter().map(Token::debug_display).collect() };
+
+        assert_eq!(describe(borrowed), describe(owned));
+    }
+
+    #[test]
+    fn test_integers_match_str_parse() {
+        let literals: Vec<String> = (0..2000u64)
+            .map(|i| i.wrapping_mul(0x9E37_79B9_7F4A_7C15) >> (i % 64))
+            .map(|n| (n % i64::MAX as u64).to_string())
+            .chain(["0".to_string(), "007".to_string(), i64::MAX.to_string()])
+            .collect();
+
+        let tokens = tokenize(&literals.join(" ")).unwrap();
+        assert_eq!(tokens.len(), literals.len());
+
+        for (literal, token) in literals.iter().zip(tokens) {
+            assert_eq!(token.kind, TokenKind::Integer(literal.parse().unwrap()));
+        }
+
+        // literals that `str::parse` rejects as too large are errors, not panics
+        for source in ["99999999999999999999", "99999999999999999999i"] {
+            assert!(source[..20].parse::<i64>().is_err());
+
+            let error = tokenize(source).unwrap_err();
+            assert!(matches!(
+                error.kind,
+                ErrorKind::Lexer(LexerError::IntegerLiteralTooLarge(ref literal))
+                    if literal == "99999999999999999999"
+            ));
+            assert_eq!((error.span.start, error.span.end), (0, 20));
+        }
+    }
+
+    #[test]
+    fn test_multibyte_spans() {
+        let source = "λ + 1";
+        let tokens = tokenize(source).unwrap();
+
+        let text = |token: &Token| &source[token.span.start..token.span.end];
+
+        assert_eq!(text(&tokens[0]), "λ");
+        assert_eq!(text(&tokens[1]), "+");
+        assert_eq!(text(&tokens[2]), "1");
+
+        let tokens = tokenize(r#""héllo" x"#).unwrap();
+        assert_eq!(tokens[0].kind, TokenKind::String("héllo".to_string()));
+        assert_eq!(tokens[1].kind, TokenKind::Identifier("x".to_string()));
+    }
+
+    #[test]
+    fn test_block_comments() {
+        let tokens = tokenize("1 /* two */ + 3").unwrap();
+        let kinds: Vec<_> = tokens.into_iter().map(|token| token.kind).collect();
+
+        assert_eq!(
+            kinds,
+            vec![
+                TokenKind::Integer(1),
+                TokenKind::Operator(Operator::Plus),
+                TokenKind::Integer(3),
+            ]
+        );
+
+        let tokens = tokenize("/* outer /* inner */ still a comment */ 4 / 2").unwrap();
+        assert_eq!(tokens.len(), 3);
+        assert_eq!(tokens[0].kind, TokenKind::Integer(4));
+
+        let tokens = tokenize("let x = 1 /* a\n b */ let y = 2").unwrap();
+        assert_eq!(tokens[4].kind, TokenKind::Newline);
+
+        let source = "1 /* open /* nested */";
+        let error = tokenize(source).unwrap_err();
+
+        assert!(matches!(
+            error.kind,
+            ErrorKind::Lexer(LexerError::UnterminatedComment)
+        ));
+        assert_eq!(&source[error.span.start..error.span.end], "/*");
+    }
+
+    #[test]
+    fn test_leading_decimal_point() {
+        let tokens = tokenize(".5 + .25").unwrap();
+
+        assert_eq!(tokens[0].kind, TokenKind::Float(0.5));
+        assert_eq!((tokens[0].span.start, tokens[0].span.end), (0, 2));
+        assert_eq!(tokens[2].kind, TokenKind::Float(0.25));
+
+        let error = tokenize("1 + . 5").unwrap_err();
+
+        assert!(matches!(
+            error.kind,
+            ErrorKind::Lexer(LexerError::UnknownSymbol(ref symbol)) if symbol == "."
+        ));
+    }
 }
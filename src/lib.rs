@@ -7,3 +7,11 @@ mod parser;
 pub mod program;
 mod token;
 mod value;
+
+pub use ast::{Node, NodeKind};
+pub use error::{Error, ErrorKind, RuntimeError};
+pub use interpreter::{Capabilities, Capability};
+pub use lexer::{Lexer, LexerConfig};
+pub use parser::{Parser, ParserConfig};
+pub use token::{Operator, Span, Token, TokenKind};
+pub use value::{ArithmeticMode, OperatorRegistry, Value, ValueKind, ValueType};
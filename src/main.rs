@@ -1,17 +1,31 @@
-use std::{env, fs};
+use std::{cell::RefCell, env, fs, rc::Rc};
 
 use owo_colors::OwoColorize;
 
-use helix::program::Program;
-use rustyline::DefaultEditor;
+use helix::{program::Program, ValueKind};
+use rustyline::{
+    completion::Completer, highlight::Highlighter, hint::Hinter, history::DefaultHistory,
+    validate::Validator, Context, Editor, Helper,
+};
+
+/// Provides tab completion in the REPL.
+struct ReplHelper {
+    /// The program being run by the REPL.
+    program: Rc<RefCell<Program>>,
+}
 
 fn main() {
     match env::args().nth(1) {
         Some(path) => run_file(&path),
-        None => repl(),
+        None => repl(&prompt(env::var("HELIX_PROMPT").ok())),
     }
 }
 
+/// Returns the REPL prompt, using `custom` in place of the default when given.
+fn prompt(custom: Option<String>) -> String {
+    custom.unwrap_or_else(|| format!("{} > ", "helix".green()))
+}
+
 fn run_file(path: &str) {
     let content = match fs::read_to_string(path) {
         Ok(content) => content,
@@ -35,27 +49,112 @@ fn run_file(path: &str) {
     }
 }
 
-fn repl() {
-    let mut rl = DefaultEditor::new().unwrap();
-    let mut program = Program::new();
+fn repl(prompt: &str) {
+    let program = Rc::new(RefCell::new(Program::new()));
 
-    loop {
-        let line = match rl.readline(&format!("{} > ", "helix".green())) {
-            Ok(line) => line,
-            Err(_) => break,
-        };
+    let mut rl = Editor::<ReplHelper, DefaultHistory>::new().unwrap();
+    rl.set_helper(Some(ReplHelper {
+        program: Rc::clone(&program),
+    }));
+
+    // toggled with `#types`, annotates results with their type
+    let mut show_types = false;
 
+    while let Ok(line) = rl.readline(prompt) {
         if line.is_empty() {
             continue;
         }
 
         rl.add_history_entry(&line).unwrap();
 
+        if line.trim() == "#types" {
+            show_types = !show_types;
+            continue;
+        }
+
+        let mut program = program.borrow_mut();
+
+        if let Some(code) = line.strip_prefix("#tokens") {
+            let main = program.add_source("<stdin>".to_string(), code.to_string());
+
+            match program.tokens(main) {
+                Ok(tokens) => tokens
+                    .iter()
+                    .for_each(|token| println!("{}", token.debug_display())),
+                Err(e) => program.pretty_print_error(e),
+            }
+
+            continue;
+        }
         let main = program.add_source("<stdin>".to_string(), line);
 
         match program.run(main) {
+            Ok(value) if show_types => println!("{}", value.display_debug()),
+            Ok(value) if value.kind == ValueKind::Null => {}
             Ok(value) => println!("{value}"),
             Err(e) => program.pretty_print_error(e),
         }
     }
 }
+
+impl ReplHelper {
+    /// Returns where the word under the cursor starts, and the candidates for completing it.
+    fn candidates(&self, line: &str, pos: usize) -> (usize, Vec<String>) {
+        let start = line[..pos]
+            .char_indices()
+            .rev()
+            .take_while(|(_, c)| c.is_alphanumeric() || *c == '_')
+            .last()
+            .map_or(pos, |(i, _)| i);
+
+        (start, self.program.borrow().completions(&line[start..pos]))
+    }
+}
+
+impl Completer for ReplHelper {
+    type Candidate = String;
+
+    fn complete(
+        &self,
+        line: &str,
+        pos: usize,
+        _: &Context<'_>,
+    ) -> rustyline::Result<(usize, Vec<String>)> {
+        Ok(self.candidates(line, pos))
+    }
+}
+
+impl Hinter for ReplHelper {
+    type Hint = String;
+}
+
+impl Highlighter for ReplHelper {}
+
+impl Validator for ReplHelper {}
+
+impl Helper for ReplHelper {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_completion_candidates() {
+        let helper = ReplHelper {
+            program: Rc::new(RefCell::new(Program::new())),
+        };
+
+        assert_eq!(
+            helper.candidates("1 + tr", 6),
+            (4, vec!["true".to_string()])
+        );
+        assert_eq!(helper.candidates("!f", 2), (1, vec!["false".to_string()]));
+        assert_eq!(helper.candidates("x", 1), (0, vec![]));
+    }
+
+    #[test]
+    fn test_custom_prompt() {
+        assert_eq!(prompt(Some(">> ".to_string())), ">> ");
+        assert!(prompt(None).contains("helix"));
+    }
+}
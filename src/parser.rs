@@ -1,3 +1,5 @@
+use std::{borrow::Cow, iter::Cloned, slice::Iter};
+
 use crate::{
     ast::NodeKind,
     cursor::Cursor,
@@ -5,32 +7,75 @@ use crate::{
     token::*,
 };
 
-pub struct Parser {
+/// Options controlling how tokens are parsed.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ParserConfig {
+    /// Whether consecutive comparisons chain like in Python, so `a < b < c`
+    /// means `a < b && b < c` with `b` evaluated once. When disabled,
+    /// comparisons are left associative like other binary operators.
+    pub chained_comparisons: bool,
+}
+
+pub struct Parser<'a, I = std::vec::IntoIter<Token>>
+where
+    I: Iterator<Item = Token>,
+{
     /// A cursor over the [`tokens`].
-    cursor: Cursor<std::vec::IntoIter<Token>>,
+    cursor: Cursor<I>,
     /// A list of all the [`Token`]s being parsed into the AST.
-    tokens: Vec<Token>,
+    tokens: Cow<'a, [Token]>,
+    /// The options used while parsing.
+    config: ParserConfig,
+    /// The errors recovered from so far, when parsing with [`Parser::parse_resilient`].
+    /// Errors are returned immediately when this is `None`.
+    recovered: Option<Vec<Error>>,
 }
 
-impl Parser {
+impl Parser<'static> {
     /// Creates a new [`Parser`].
     pub fn new(tokens: Vec<Token>) -> Self {
         Parser {
-            tokens: tokens.clone(),
+            tokens: Cow::Owned(tokens.clone()),
             cursor: Cursor::new(tokens.into_iter()),
+            config: ParserConfig::default(),
+            recovered: None,
         }
     }
+}
+
+impl<'a> Parser<'a, Cloned<Iter<'a, Token>>> {
+    /// Creates a new [`Parser`] over a borrowed list of tokens, avoiding a copy
+    /// of the whole list.
+    pub fn from_slice(tokens: &'a [Token]) -> Self {
+        Parser {
+            tokens: Cow::Borrowed(tokens),
+            cursor: Cursor::new(tokens.iter().cloned()),
+            config: ParserConfig::default(),
+            recovered: None,
+        }
+    }
+}
+
+impl<'a, I> Parser<'a, I>
+where
+    I: Iterator<Item = Token>,
+{
+    /// Sets the options used while parsing.
+    pub fn with_config(mut self, config: ParserConfig) -> Self {
+        self.config = config;
+        self
+    }
 
     pub fn parse(mut self) -> Result<ASTNode> {
-        let node = self.expression()?;
+        let node = self.statements()?;
 
         if let Some(token) = self.cursor.advance() {
             let span = token.span;
 
             let kind = match token.kind {
                 TokenKind::Parenthesis(Parenthesis {
-                    kind: ParenthesisKind::Round,
                     opening: Opening::Close,
+                    ..
                 }) => ParserError::MismatchedParenthesis,
 
                 _ => ParserError::ExpectedEndOfFile(token),
@@ -45,8 +90,277 @@ impl Parser {
         Ok(node)
     }
 
-    /// equality (("&&" | "||") equality)*
+    /// Parses as much of the input as possible, returning a tree with
+    /// [`NodeKind::Error`] in place of anything that failed to parse, along
+    /// with every error encountered.
+    pub fn parse_resilient(mut self) -> (Option<ASTNode>, Vec<Error>) {
+        self.recovered = Some(Vec::new());
+
+        let node = self.statements();
+        let mut errors = self.recovered.take().unwrap_or_default();
+
+        let node = match node {
+            Ok(node) => Some(node),
+            Err(error) => {
+                errors.push(error);
+                None
+            }
+        };
+
+        if let Some(token) = self.cursor.advance() {
+            errors.push(Error {
+                span: token.span,
+                kind: ParserError::ExpectedEndOfFile(token).into(),
+            });
+        }
+
+        (node, errors)
+    }
+
+    /// Records `error` and returns a placeholder node when parsing resiliently,
+    /// otherwise returns the error.
+    fn recover(&mut self, error: Error) -> Result<ASTNode> {
+        let Some(recovered) = &mut self.recovered else {
+            return Err(error);
+        };
+
+        let span = error.span;
+        recovered.push(error);
+
+        Ok(ASTNode::new(NodeKind::Error, span))
+    }
+
+    /// SEPARATOR* statement (SEPARATOR+ statement)* SEPARATOR*
+    ///
+    /// Runs of separators are collapsed, so there are no empty statements, and a
+    /// program without any statements is `null`. A single statement is returned as
+    /// is, rather than wrapped in a block.
+    fn statements(&mut self) -> Result<ASTNode> {
+        let mut statements = Vec::new();
+
+        loop {
+            self.cursor.advance_while(Self::is_separator);
+
+            let at_end = self.cursor.peek().is_none() || self.is_closing(ParenthesisKind::Curly);
+
+            if at_end && statements.is_empty() {
+                return Ok(ASTNode::new(NodeKind::Null, self.end_of_file_span()));
+            }
+
+            if at_end {
+                break;
+            }
+
+            statements.push(self.statement()?);
+
+            if !self.cursor.peek().is_some_and(Self::is_separator) {
+                break;
+            }
+        }
+
+        if statements.len() == 1 {
+            return Ok(statements.pop().unwrap());
+        }
+
+        let span = statements[0]
+            .span
+            .merge(statements[statements.len() - 1].span);
+
+        Ok(ASTNode::new(NodeKind::Block { statements }, span))
+    }
+
+    /// "let" IDENT "=" expression | "print" expression | expression
+    fn statement(&mut self) -> Result<ASTNode> {
+        let token = self.peek()?;
+
+        if token.kind == TokenKind::Keyword(Keyword::Print) {
+            self.cursor.advance();
+
+            let expression = self.expression()?;
+            let span = token.span.merge(expression.span);
+
+            return Ok(ASTNode::new(
+                NodeKind::Print {
+                    expression: Box::new(expression),
+                },
+                span,
+            ));
+        }
+
+        if token.kind != TokenKind::Keyword(Keyword::Let) {
+            return self.expression();
+        }
+
+        self.cursor.advance();
+
+        let name = match self.consume()? {
+            Token {
+                kind: TokenKind::Identifier(name),
+                ..
+            } => name,
+            token => {
+                return Err(Error {
+                    span: token.span,
+                    kind: ParserError::UnexpectedToken(token).into(),
+                })
+            }
+        };
+
+        let assign = self.consume()?;
+
+        if assign.kind != TokenKind::Operator(Operator::Assign) {
+            return Err(Error {
+                span: assign.span,
+                kind: ParserError::UnexpectedToken(assign).into(),
+            });
+        }
+
+        let value = self.expression()?;
+        let span = token.span.merge(value.span);
+
+        Ok(ASTNode::new(
+            NodeKind::Let {
+                name,
+                value: Box::new(value),
+            },
+            span,
+        ))
+    }
+
+    /// "{" statements "}"
+    fn block(&mut self) -> Result<ASTNode> {
+        let open = self.consume()?;
+
+        if open.kind
+            != TokenKind::Parenthesis(Parenthesis {
+                kind: ParenthesisKind::Curly,
+                opening: Opening::Open,
+            })
+        {
+            return Err(Error {
+                span: open.span,
+                kind: ParserError::UnexpectedToken(open).into(),
+            });
+        }
+
+        self.cursor.advance_while(Self::is_separator);
+
+        // an empty block evaluates to null
+        if self.is_closing(ParenthesisKind::Curly) {
+            let close = self.consume()?;
+            return Ok(ASTNode::new(NodeKind::Null, open.span.merge(close.span)));
+        }
+
+        let body = self.statements()?;
+        self.expect_closing(ParenthesisKind::Curly)?;
+
+        Ok(body)
+    }
+
+    /// Returns whether the token ends a statement.
+    fn is_separator(token: &Token) -> bool {
+        matches!(token.kind, TokenKind::Newline | TokenKind::Semicolon)
+    }
+
+    /// "if" expression block ("else" (if | block))?
+    ///
+    /// Expects the `if` keyword to have already been consumed.
+    fn if_expression(&mut self, keyword: Token) -> Result<ASTNode> {
+        let condition = self.expression()?;
+        let then_branch = self.block()?;
+
+        let mut else_branch = None;
+
+        if let Some(TokenKind::Keyword(Keyword::Else)) = self.cursor.peek().map(|t| &t.kind) {
+            self.cursor.advance();
+
+            let branch = match self.peek()? {
+                token @ Token {
+                    kind: TokenKind::Keyword(Keyword::If),
+                    ..
+                } => {
+                    self.cursor.advance();
+                    self.if_expression(token)?
+                }
+                _ => self.block()?,
+            };
+
+            else_branch = Some(Box::new(branch));
+        }
+
+        let span = keyword.span.merge(self.tokens[self.cursor.pos - 1].span);
+
+        Ok(ASTNode::new(
+            NodeKind::If {
+                condition: Box::new(condition),
+                then_branch: Box::new(then_branch),
+                else_branch,
+            },
+            span,
+        ))
+    }
+
+    /// "while" expression block
+    ///
+    /// Expects the `while` keyword to have already been consumed.
+    fn while_loop(&mut self, keyword: Token) -> Result<ASTNode> {
+        let condition = self.expression()?;
+        let body = self.block()?;
+
+        let span = keyword.span.merge(self.tokens[self.cursor.pos - 1].span);
+
+        Ok(ASTNode::new(
+            NodeKind::While {
+                condition: Box::new(condition),
+                body: Box::new(body),
+            },
+            span,
+        ))
+    }
+
+    /// IDENT "=" expression | logical
+    ///
+    /// Assignment binds looser than every operator, and is right associative.
     fn expression(&mut self) -> Result<ASTNode> {
+        let is_assignment = matches!(
+            self.tokens.get(self.cursor.pos..self.cursor.pos + 2),
+            Some([
+                Token {
+                    kind: TokenKind::Identifier(_),
+                    ..
+                },
+                Token {
+                    kind: TokenKind::Operator(Operator::Assign),
+                    ..
+                }
+            ])
+        );
+
+        if !is_assignment {
+            return self.logical();
+        }
+
+        let target = self.consume()?;
+        let TokenKind::Identifier(name) = target.kind else {
+            unreachable!("assignment target should be an identifier");
+        };
+
+        self.cursor.advance();
+
+        let value = self.expression()?;
+        let span = target.span.merge(value.span);
+
+        Ok(ASTNode::new(
+            NodeKind::Assign {
+                name,
+                value: Box::new(value),
+            },
+            span,
+        ))
+    }
+
+    /// equality (("&&" | "||") equality)*
+    fn logical(&mut self) -> Result<ASTNode> {
         self.reduce_binary_operators(Self::equality, &[Operator::And, Operator::Or])
     }
 
@@ -57,15 +371,60 @@ impl Parser {
 
     /// term ((">" | ">=" | "<" | "<=") term)*
     fn comparison(&mut self) -> Result<ASTNode> {
-        self.reduce_binary_operators(
-            Self::term,
-            &[
-                Operator::LessThan,
-                Operator::LessThanEquals,
-                Operator::GreaterThan,
-                Operator::GreaterThanEquals,
-            ],
-        )
+        const COMPARISONS: &[Operator] = &[
+            Operator::LessThan,
+            Operator::LessThanEquals,
+            Operator::GreaterThan,
+            Operator::GreaterThanEquals,
+        ];
+
+        if !self.config.chained_comparisons {
+            return self.reduce_binary_operators(Self::term, COMPARISONS);
+        }
+
+        let first = self.term()?;
+        let mut rest = Vec::new();
+
+        while let Some(token) = self.cursor.peek().cloned() {
+            let Some(op) = Operator::from_token_kind(&token.kind) else {
+                break;
+            };
+
+            if !COMPARISONS.contains(&op) {
+                break;
+            }
+
+            let _ = self.consume();
+            rest.push((op, self.term()?));
+        }
+
+        // a single comparison is an ordinary binary operation
+        if rest.len() <= 1 {
+            let Some((operator, rhs)) = rest.pop() else {
+                return Ok(first);
+            };
+
+            let span = first.span.merge(rhs.span);
+
+            return Ok(ASTNode::new(
+                NodeKind::BinaryOp {
+                    lhs: Box::new(first),
+                    operator,
+                    rhs: Box::new(rhs),
+                },
+                span,
+            ));
+        }
+
+        let span = first.span.merge(rest.last().unwrap().1.span);
+
+        Ok(ASTNode::new(
+            NodeKind::ComparisonChain {
+                first: Box::new(first),
+                rest,
+            },
+            span,
+        ))
     }
 
     /// factor (("+" | "-") factor)*
@@ -73,12 +432,43 @@ impl Parser {
         self.reduce_binary_operators(Self::factor, &[Operator::Plus, Operator::Minus])
     }
 
-    /// unary (("*" | "/") unary)*
+    /// unary (("*" | "/" | "%") unary)*
     fn factor(&mut self) -> Result<ASTNode> {
-        self.reduce_binary_operators(Self::unary, &[Operator::Multiply, Operator::Divide])
+        self.reduce_binary_operators(
+            Self::unary,
+            &[Operator::Multiply, Operator::Divide, Operator::Modulo],
+        )
+    }
+
+    /// postfix ("**" unary)?
+    ///
+    /// Exponentiation is right associative, so `2 ** 3 ** 2` is `2 ** (3 ** 2)`, and
+    /// binds tighter than a unary operator on its left, so `-2 ** 2` is `-(2 ** 2)`.
+    fn power(&mut self) -> Result<ASTNode> {
+        let lhs = self.postfix()?;
+
+        if self.cursor.peek().map(|token| &token.kind)
+            != Some(&TokenKind::Operator(Operator::Power))
+        {
+            return Ok(lhs);
+        }
+
+        self.cursor.advance();
+
+        let rhs = self.unary()?;
+        let span = lhs.span.merge(rhs.span);
+
+        Ok(ASTNode::new(
+            NodeKind::BinaryOp {
+                lhs: Box::new(lhs),
+                operator: Operator::Power,
+                rhs: Box::new(rhs),
+            },
+            span,
+        ))
     }
 
-    /// ("+" | "-" | "!")* unary | atom
+    /// ("+" | "-" | "!") unary | power
     fn unary(&mut self) -> Result<ASTNode> {
         let token = self.peek()?;
 
@@ -87,7 +477,7 @@ impl Parser {
                 self.cursor.advance();
 
                 let Some(op) = UnaryOperator::from_operator(op) else {
-                    return Err(Error {
+                    return self.recover(Error {
                         span: token.span,
                         kind: ParserError::InvalidUnaryOperator(op).into(),
                     });
@@ -98,18 +488,65 @@ impl Parser {
                     operand: Box::new(self.unary()?),
                 };
 
-                let span = token.span.start..self.tokens[self.cursor.pos - 1].span.end;
+                let span = token.span.merge(self.tokens[self.cursor.pos - 1].span);
 
-                Ok(ASTNode::new(kind, Span::new(span, token.span.source)))
+                Ok(ASTNode::new(kind, span))
             }
 
-            _ => self.atom(),
+            _ => self.power(),
         }
     }
 
-    /// int | float | "(" expression ")"
+    /// atom ("!" | "[" expression "]")*
+    ///
+    /// A `!` directly following an operand is a factorial rather than a logical not.
+    fn postfix(&mut self) -> Result<ASTNode> {
+        let mut operand = self.atom()?;
+
+        while let Some(token) = self.cursor.peek().cloned() {
+            let start = operand.span;
+
+            let kind = match token.kind {
+                TokenKind::Operator(Operator::Not) => {
+                    self.cursor.advance();
+
+                    NodeKind::UnaryOp {
+                        operator: UnaryOperator::Factorial,
+                        operand: Box::new(operand),
+                    }
+                }
+
+                TokenKind::Parenthesis(Parenthesis {
+                    kind: ParenthesisKind::Square,
+                    opening: Opening::Open,
+                }) => {
+                    self.cursor.advance();
+
+                    let index = self.expression()?;
+                    self.expect_closing(ParenthesisKind::Square)?;
+
+                    NodeKind::Index {
+                        target: Box::new(operand),
+                        index: Box::new(index),
+                    }
+                }
+
+                _ => break,
+            };
+
+            let span = start.merge(self.tokens[self.cursor.pos - 1].span);
+            operand = ASTNode::new(kind, span);
+        }
+
+        Ok(operand)
+    }
+
+    /// int | float | "(" expression ")" | "(" (expression ",")+ expression? ")"
     fn atom(&mut self) -> Result<ASTNode> {
-        let token = self.consume()?;
+        let token = match self.consume() {
+            Ok(token) => token,
+            Err(error) => return self.recover(error),
+        };
 
         let kind = match token.kind {
             TokenKind::Float(lit) => NodeKind::Float(lit),
@@ -117,28 +554,77 @@ impl Parser {
 
             TokenKind::String(lit) => NodeKind::String(lit),
 
-            TokenKind::Keyword(keyword) => match keyword {
-                Keyword::True => NodeKind::Boolean(true),
-                Keyword::False => NodeKind::Boolean(false),
-            },
+            TokenKind::Keyword(Keyword::True) => NodeKind::Boolean(true),
+            TokenKind::Keyword(Keyword::False) => NodeKind::Boolean(false),
+            TokenKind::Keyword(Keyword::Nan) => NodeKind::Float(f64::NAN),
+            TokenKind::Keyword(Keyword::Inf) => NodeKind::Float(f64::INFINITY),
+            TokenKind::Keyword(Keyword::Null) => NodeKind::Null,
 
             TokenKind::Identifier(ident) => NodeKind::Identifier(ident),
 
+            TokenKind::Keyword(Keyword::If) => return self.if_expression(token),
+            TokenKind::Keyword(Keyword::While) => return self.while_loop(token),
+            TokenKind::Keyword(Keyword::Break) => NodeKind::Break,
+            TokenKind::Keyword(Keyword::Continue) => NodeKind::Continue,
+
             TokenKind::Parenthesis(Parenthesis {
                 kind: ParenthesisKind::Round,
                 opening: Opening::Open,
             }) => {
                 let expr = self.expression()?;
-                self.consume().map_err(|e| Error {
-                    span: e.span,
-                    kind: ParserError::MismatchedParenthesis.into(),
-                })?;
 
-                return Ok(expr);
+                // a comma makes this a tuple rather than a grouping
+                if self.cursor.peek().map(|token| &token.kind) != Some(&TokenKind::Comma) {
+                    self.expect_closing(ParenthesisKind::Round)?;
+                    return Ok(expr);
+                }
+
+                let mut items = vec![expr];
+
+                while let Some(TokenKind::Comma) = self.cursor.peek().map(|token| &token.kind) {
+                    self.cursor.advance();
+
+                    if self.is_closing(ParenthesisKind::Round) {
+                        break;
+                    }
+
+                    items.push(self.expression()?);
+                }
+
+                let close = self.expect_closing(ParenthesisKind::Round)?;
+
+                return Ok(ASTNode::new(
+                    NodeKind::Tuple(items),
+                    token.span.merge(close.span),
+                ));
+            }
+
+            TokenKind::Parenthesis(Parenthesis {
+                kind: ParenthesisKind::Square,
+                opening: Opening::Open,
+            }) => {
+                let mut items = Vec::new();
+
+                while !self.is_closing(ParenthesisKind::Square) {
+                    items.push(self.expression()?);
+
+                    if self.cursor.peek().map(|token| &token.kind) != Some(&TokenKind::Comma) {
+                        break;
+                    }
+
+                    self.cursor.advance();
+                }
+
+                let close = self.expect_closing(ParenthesisKind::Square)?;
+
+                return Ok(ASTNode::new(
+                    NodeKind::Array(items),
+                    token.span.merge(close.span),
+                ));
             }
 
             _ => {
-                return Err(Error {
+                return self.recover(Error {
                     span: token.span,
                     kind: ParserError::UnexpectedToken(token).into(),
                 })
@@ -148,6 +634,37 @@ impl Parser {
         Ok(ASTNode::new(kind, token.span))
     }
 
+    /// Returns whether the next token closes a parenthesis of the given kind.
+    fn is_closing(&mut self, kind: ParenthesisKind) -> bool {
+        self.cursor.peek().map(|token| &token.kind)
+            == Some(&TokenKind::Parenthesis(Parenthesis {
+                kind,
+                opening: Opening::Close,
+            }))
+    }
+
+    /// Consumes a closing parenthesis of the given kind.
+    fn expect_closing(&mut self, kind: ParenthesisKind) -> Result<Token> {
+        let token = self.consume().map_err(|e| Error {
+            span: e.span,
+            kind: ParserError::MismatchedParenthesis.into(),
+        })?;
+
+        if token.kind
+            != TokenKind::Parenthesis(Parenthesis {
+                kind,
+                opening: Opening::Close,
+            })
+        {
+            return Err(Error {
+                span: token.span,
+                kind: ParserError::UnexpectedToken(token).into(),
+            });
+        }
+
+        Ok(token)
+    }
+
     fn reduce_binary_operators<F>(&mut self, reducer: F, operators: &[Operator]) -> Result<ASTNode>
     where
         F: Fn(&mut Self) -> Result<ASTNode>,
@@ -166,7 +683,7 @@ impl Parser {
             let _ = self.consume();
             let rhs = reducer(self)?;
 
-            let span = lhs.span.start..rhs.span.end;
+            let span = lhs.span.merge(rhs.span);
 
             lhs = ASTNode::new(
                 NodeKind::BinaryOp {
@@ -174,7 +691,7 @@ impl Parser {
                     lhs: Box::new(lhs),
                     rhs: Box::new(rhs),
                 },
-                Span::new(span, token.span.source),
+                span,
             );
         }
 
@@ -182,49 +699,66 @@ impl Parser {
     }
 
     fn peek(&mut self) -> Result<Token> {
+        let span = self.end_of_file_span();
+
         self.cursor
             .peek()
             .ok_or(Error {
-                span: {
-                    let last = self.tokens.last().unwrap();
-                    Span::new(last.span.end - 1..last.span.end, last.span.source)
-                },
+                span,
                 kind: ParserError::UnexpectedEndOfFile.into(),
             })
             .cloned()
     }
 
     fn consume(&mut self) -> Result<Token> {
+        let span = self.end_of_file_span();
+
         self.cursor.advance().ok_or(Error {
-            span: {
-                let last = self.tokens.last().unwrap();
-                Span::new(last.span.end - 1..last.span.end, last.span.source)
-            },
+            span,
             kind: ParserError::UnexpectedEndOfFile.into(),
         })
     }
+
+    /// The span of the last token, or a synthetic span when there are no tokens at all.
+    fn end_of_file_span(&self) -> Span {
+        self.tokens
+            .last()
+            .map_or_else(Span::synthetic, |last| last.span)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use slotmap::{DefaultKey, Key};
 
-    use crate::{lexer::Lexer, program::Source};
+    use crate::{
+        ast::Node,
+        error::ErrorKind,
+        lexer::{Lexer, LexerConfig},
+        program::Source,
+    };
 
     use super::*;
 
-    fn parse(source: &str) -> Result<NodeKind> {
-        let tokens = Lexer::new(
+    fn tokenize(source: &str) -> Vec<Token> {
+        tokenize_with_config(source, LexerConfig::default())
+    }
+
+    fn tokenize_with_config(source: &str, config: LexerConfig) -> Vec<Token> {
+        Lexer::new(
             DefaultKey::null(),
             &Source {
                 name: "<test>".to_string(),
                 content: source.to_string(),
             },
         )
+        .with_config(config)
         .tokenize()
-        .expect("test case did not tokenize properly");
+        .expect("test case did not tokenize properly")
+    }
 
-        Parser::new(tokens).parse().map(|node| node.kind)
+    fn parse(source: &str) -> Result<NodeKind> {
+        Parser::new(tokenize(source)).parse().map(|node| node.kind)
     }
 
     #[test]
@@ -265,4 +799,347 @@ mod tests {
             }
         ));
     }
+
+    #[test]
+    fn test_parse_from_slice() {
+        let tokens = tokenize("1 + -2 * (3 - 4)");
+
+        let borrowed = Parser::from_slice(&tokens).parse().unwrap();
+        let owned = Parser::new(tokens.clone()).parse().unwrap();
+
+        assert_eq!(borrowed, owned);
+        assert_eq!(
+            Parser::from_slice(&tokens[..4]).parse().unwrap(),
+            Parser::new(tokens[..4].to_vec()).parse().unwrap()
+        );
+    }
+
+    #[test]
+    fn test_word_operators() {
+        let config = LexerConfig {
+            word_operators: true,
+        };
+
+        let words = Parser::new(tokenize_with_config("not true and false or true", config))
+            .parse()
+            .unwrap();
+        let symbols = Parser::new(tokenize("!true && false || true"))
+            .parse()
+            .unwrap();
+
+        assert!(words.structural_eq_ignoring_spans(&symbols));
+    }
+
+    #[test]
+    fn test_factorial() {
+        let Ok(NodeKind::UnaryOp {
+            operator: UnaryOperator::Factorial,
+            operand,
+        }) = parse("5!")
+        else {
+            panic!();
+        };
+
+        assert_eq!(operand.kind, NodeKind::Integer(5));
+
+        // prefix operators bind looser than the postfix factorial
+        let Ok(NodeKind::UnaryOp {
+            operator: UnaryOperator::Minus,
+            operand,
+        }) = parse("-1!")
+        else {
+            panic!();
+        };
+
+        assert!(matches!(
+            operand.kind,
+            NodeKind::UnaryOp {
+                operator: UnaryOperator::Factorial,
+                ..
+            }
+        ));
+
+        let Ok(NodeKind::UnaryOp {
+            operator: UnaryOperator::Factorial,
+            operand,
+        }) = parse("(-1)!")
+        else {
+            panic!();
+        };
+
+        assert!(matches!(
+            operand.kind,
+            NodeKind::UnaryOp {
+                operator: UnaryOperator::Minus,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn test_chained_comparisons() {
+        let config = ParserConfig {
+            chained_comparisons: true,
+        };
+
+        let Ok(Node {
+            kind: NodeKind::ComparisonChain { first, rest },
+            ..
+        }) = Parser::new(tokenize("1 < 2 <= 3"))
+            .with_config(config)
+            .parse()
+        else {
+            panic!();
+        };
+
+        assert_eq!(first.kind, NodeKind::Integer(1));
+        assert_eq!(rest.len(), 2);
+        assert_eq!(rest[0].0, Operator::LessThan);
+        assert_eq!(rest[0].1.kind, NodeKind::Integer(2));
+        assert_eq!(rest[1].0, Operator::LessThanEquals);
+        assert_eq!(rest[1].1.kind, NodeKind::Integer(3));
+
+        // a single comparison is not a chain
+        let single = Parser::new(tokenize("1 < 2")).with_config(config).parse();
+        assert_eq!(single.unwrap().kind, parse("1 < 2").unwrap());
+
+        // without the option, comparisons are left associative
+        let Ok(NodeKind::BinaryOp { lhs, .. }) = parse("1 < 2 < 3") else {
+            panic!();
+        };
+
+        assert!(matches!(
+            lhs.kind,
+            NodeKind::BinaryOp {
+                operator: Operator::LessThan,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn test_tuples() {
+        let Ok(NodeKind::Tuple(items)) = parse("(1, 2.5, true)") else {
+            panic!();
+        };
+        assert_eq!(items.len(), 3);
+
+        // a trailing comma makes a single element tuple, without one it is a grouping
+        assert!(matches!(parse("(1,)"), Ok(NodeKind::Tuple(items)) if items.len() == 1));
+        assert_eq!(parse("(1)").unwrap(), NodeKind::Integer(1));
+    }
+
+    #[test]
+    fn test_arrays() {
+        let Ok(NodeKind::Array(items)) = parse("[1, 2.5, true,]") else {
+            panic!();
+        };
+        assert_eq!(items.len(), 3);
+
+        assert!(matches!(parse("[]"), Ok(NodeKind::Array(items)) if items.is_empty()));
+        assert!(matches!(parse("[1][0]"), Ok(NodeKind::Index { .. })));
+        assert!(parse("[1 2]").is_err());
+        assert!(parse("[1,").is_err());
+
+        assert!(matches!(parse("(1, 2)[0]"), Ok(NodeKind::Index { .. })));
+        assert!(parse("(1, 2").is_err());
+    }
+
+    #[test]
+    fn test_let() {
+        let Ok(NodeKind::Let { name, value }) = parse("let x = 1 + 2") else {
+            panic!();
+        };
+
+        assert_eq!(name, "x");
+        assert!(matches!(value.kind, NodeKind::BinaryOp { .. }));
+
+        assert!(parse("let 1 = 2").is_err());
+        assert!(parse("let x 2").is_err());
+    }
+
+    #[test]
+    fn test_assignment() {
+        let Ok(NodeKind::Assign { name, value }) = parse("a = 1 + 2") else {
+            panic!();
+        };
+
+        assert_eq!(name, "a");
+        assert!(matches!(
+            value.kind,
+            NodeKind::BinaryOp {
+                operator: Operator::Plus,
+                ..
+            }
+        ));
+
+        // assignment is right associative
+        let Ok(NodeKind::Assign { name, value }) = parse("a = b = 1") else {
+            panic!();
+        };
+
+        assert_eq!(name, "a");
+        assert!(matches!(
+            value.kind,
+            NodeKind::Assign { ref name, .. } if name == "b"
+        ));
+
+        // `==` is still a comparison
+        assert!(matches!(parse("a == 1"), Ok(NodeKind::BinaryOp { .. })));
+    }
+
+    #[test]
+    fn test_parse_resilient() {
+        let (node, errors) = Parser::new(tokenize("1 + , * 3")).parse_resilient();
+
+        let Some(Node {
+            kind: NodeKind::BinaryOp { lhs, rhs, .. },
+            ..
+        }) = node
+        else {
+            panic!();
+        };
+
+        assert_eq!(lhs.kind, NodeKind::Integer(1));
+        assert!(matches!(
+            rhs.kind,
+            NodeKind::BinaryOp { ref lhs, .. } if lhs.kind == NodeKind::Error
+        ));
+
+        assert_eq!(errors.len(), 1);
+        assert!(matches!(
+            errors[0].kind,
+            ErrorKind::Parser(ParserError::UnexpectedToken(_))
+        ));
+
+        // the ordinary parser still stops at the first error
+        assert!(parse("1 + , * 3").is_err());
+    }
+
+    #[test]
+    fn test_statements() {
+        let Ok(NodeKind::Block { statements }) = parse("\n1 + 1\n\nlet x = 2\n") else {
+            panic!();
+        };
+
+        assert_eq!(statements.len(), 2);
+        assert!(matches!(statements[1].kind, NodeKind::Let { .. }));
+
+        // a single statement is not wrapped in a block
+        assert_eq!(parse("1\n").unwrap(), NodeKind::Integer(1));
+
+        assert!(parse("1 2").is_err());
+    }
+
+    #[test]
+    fn test_multiline_expressions() {
+        assert!(matches!(parse("1 +\n2"), Ok(NodeKind::BinaryOp { .. })));
+        assert!(matches!(parse("(1\n+ 2)"), Ok(NodeKind::BinaryOp { .. })));
+        assert!(matches!(parse("(1,\n 2)"), Ok(NodeKind::Tuple(items)) if items.len() == 2));
+        assert!(matches!(parse("[1,\n 2]"), Ok(NodeKind::Array(items)) if items.len() == 2));
+
+        // blocks inside parentheses still separate statements by line
+        let Ok(NodeKind::If { then_branch, .. }) = parse("(if true {\n 1\n 2\n})") else {
+            panic!();
+        };
+        assert!(matches!(then_branch.kind, NodeKind::Block { .. }));
+
+        // a postfix `!` can end a statement
+        assert!(matches!(parse("3!\n4"), Ok(NodeKind::Block { .. })));
+    }
+
+    #[test]
+    fn test_empty_programs() {
+        for source in ["", "   ", ";;", "\n", "/* x */", "\n/* x */;\n"] {
+            assert_eq!(parse(source).unwrap(), NodeKind::Null, "{source:?}");
+        }
+
+        assert!(parse("}").is_err());
+    }
+
+    #[test]
+    fn test_separators() {
+        let Ok(NodeKind::Block { statements }) = parse("1;;2") else {
+            panic!();
+        };
+
+        assert_eq!(statements.len(), 2);
+        assert_eq!(statements[1].kind, NodeKind::Integer(2));
+
+        let Ok(NodeKind::Block { statements }) = parse("let x = 1;\n x;\n") else {
+            panic!();
+        };
+
+        assert_eq!(statements.len(), 2);
+        assert!(matches!(statements[1].kind, NodeKind::Identifier(_)));
+
+        assert!(matches!(parse("1 + 2"), Ok(NodeKind::BinaryOp { .. })));
+
+        let Ok(NodeKind::If { then_branch, .. }) = parse("if true { ;\n }") else {
+            panic!();
+        };
+
+        assert_eq!(then_branch.kind, NodeKind::Null);
+    }
+
+    #[test]
+    fn test_if() {
+        let Ok(NodeKind::If {
+            condition,
+            then_branch,
+            else_branch: Some(else_branch),
+        }) = parse("if 1 < 2 { 1 } else if false { 2 } else {\n 3\n}")
+        else {
+            panic!();
+        };
+
+        assert!(matches!(condition.kind, NodeKind::BinaryOp { .. }));
+        assert_eq!(then_branch.kind, NodeKind::Integer(1));
+        assert!(matches!(
+            else_branch.kind,
+            NodeKind::If {
+                else_branch: Some(_),
+                ..
+            }
+        ));
+
+        assert!(matches!(
+            parse("if true { 1 }"),
+            Ok(NodeKind::If {
+                else_branch: None,
+                ..
+            })
+        ));
+        assert!(parse("if true 1").is_err());
+        assert!(parse("if true { 1").is_err());
+    }
+
+    #[test]
+    fn test_while() {
+        let Ok(NodeKind::While { condition, body }) = parse("while x < 3 {\n x = x + 1\n}") else {
+            panic!();
+        };
+
+        assert!(matches!(condition.kind, NodeKind::BinaryOp { .. }));
+        assert!(matches!(body.kind, NodeKind::Assign { .. }));
+
+        assert!(parse("while true").is_err());
+    }
+
+    #[test]
+    fn test_modulo() {
+        // `%` binds as tightly as `*` and `/`
+        let Ok(NodeKind::BinaryOp { operator, rhs, .. }) = parse("1 + 7 % 3") else {
+            panic!();
+        };
+
+        assert_eq!(operator, Operator::Plus);
+        assert!(matches!(
+            rhs.kind,
+            NodeKind::BinaryOp {
+                operator: Operator::Modulo,
+                ..
+            }
+        ));
+    }
 }
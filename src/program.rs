@@ -1,16 +1,18 @@
 use slotmap::{DefaultKey, SlotMap};
 
 use crate::{
+    ast::NodeKind,
     error::{Error, Result},
-    interpreter::Interpreter,
-    lexer::Lexer,
-    parser::Parser,
-    token::{ASTNode, Token},
-    value::Value,
+    interpreter::{Capabilities, Interpreter},
+    lexer::{Lexer, LexerConfig},
+    parser::{Parser, ParserConfig},
+    token::{ASTNode, Keyword, Span, Token},
+    value::{ArithmeticMode, OperatorRegistry, Value},
 };
 
 /// A source file that contains some source code, and potentially
 /// parsed ast.
+#[derive(Debug, Clone)]
 pub struct Source {
     /// The name of the source.
     pub name: String,
@@ -22,18 +24,37 @@ pub struct Source {
 /// and an interpreter for the program
 pub struct Program {
     sources: SlotMap<DefaultKey, Source>,
+    /// The options used when tokenizing sources.
+    lexer_config: LexerConfig,
+    /// The options used when parsing sources.
+    parser_config: ParserConfig,
+    /// Whether rendered errors include the lines surrounding the error.
+    context_lines: bool,
+    /// The interpreter that runs sources, kept between runs so that variables
+    /// declared by one source are visible to the next.
+    interpreter: Interpreter,
 }
 
 impl Source {
     /// Lexes the source file.
-    fn lex(&self, key: DefaultKey) -> Result<Vec<Token>> {
-        Lexer::new(key, self).tokenize()
+    fn lex(&self, key: DefaultKey, config: LexerConfig) -> Result<Vec<Token>> {
+        Lexer::new(key, self).with_config(config).tokenize()
     }
 
     /// Lexes and parses the source file.
-    pub fn parse(&self, key: DefaultKey) -> Result<ASTNode> {
-        let tokens = self.lex(key)?;
-        Parser::new(tokens).parse()
+    pub fn parse(
+        &self,
+        key: DefaultKey,
+        lexer_config: LexerConfig,
+        parser_config: ParserConfig,
+    ) -> Result<ASTNode> {
+        let tokens = self.lex(key, lexer_config)?;
+        Parser::new(tokens).with_config(parser_config).parse()
+    }
+
+    /// Returns the text of the given (1-based) line, without its line ending.
+    pub fn line_text(&self, line: usize) -> Option<&str> {
+        self.content.lines().nth(line.checked_sub(1)?)
     }
 }
 
@@ -41,27 +62,205 @@ impl Program {
     pub fn new() -> Self {
         Self {
             sources: SlotMap::new(),
+            lexer_config: LexerConfig::default(),
+            parser_config: ParserConfig::default(),
+            context_lines: false,
+            interpreter: Interpreter::new(),
         }
     }
 
+    /// Sets the options used when tokenizing sources.
+    pub fn set_lexer_config(&mut self, config: LexerConfig) {
+        self.lexer_config = config;
+    }
+
+    /// Sets the options used when parsing sources.
+    pub fn set_parser_config(&mut self, config: ParserConfig) {
+        self.parser_config = config;
+    }
+
+    /// Sets how integer overflow is handled when running sources.
+    pub fn set_arithmetic_mode(&mut self, mode: ArithmeticMode) {
+        self.interpreter.arithmetic_mode = mode;
+    }
+
+    /// Returns the registry of additional binary operator handlers.
+    pub fn operators_mut(&mut self) -> &mut OperatorRegistry {
+        &mut self.interpreter.operators
+    }
+
+    /// Sets what sources run by this program are permitted to do.
+    pub fn set_capabilities(&mut self, capabilities: Capabilities) {
+        self.interpreter.capabilities = capabilities;
+    }
+
+    /// Sets whether `"count: " + 5` concatenates rather than raising an error.
+    pub fn set_implicit_string_coercion(&mut self, enabled: bool) {
+        self.interpreter.implicit_string_coercion = enabled;
+    }
+
+    /// Discards every variable declared by previously run sources, keeping
+    /// the interpreter's configuration.
+    pub fn reset_interpreter(&mut self) {
+        self.interpreter.reset();
+    }
+
+    /// Sets whether rendered errors show one line of context before and
+    /// after the erroring line.
+    pub fn set_context_lines(&mut self, enabled: bool) {
+        self.context_lines = enabled;
+    }
+
     /// Register a new source file with the program.
     pub fn add_source(&mut self, name: String, content: String) -> DefaultKey {
         self.sources.insert(Source { name, content })
     }
 
+    /// Lexes the given source file by key, without parsing or running it.
+    pub fn tokens(&self, key: DefaultKey) -> Result<Vec<Token>> {
+        let source = self.sources.get(key).expect("source does not exist");
+        source.lex(key, self.lexer_config)
+    }
+
+    /// Lexes and parses the given source file by key, without running it.
+    pub fn ast(&self, key: DefaultKey) -> Result<ASTNode> {
+        let source = self.sources.get(key).expect("source does not exist");
+        source.parse(key, self.lexer_config, self.parser_config)
+    }
+
+    /// Lists the names and spans of the `let` bindings at the top level of the
+    /// given source file, without running it.
+    pub fn definitions(&self, key: DefaultKey) -> Result<Vec<(String, Span)>> {
+        let ast = self.ast(key)?;
+
+        let statements = match ast.kind {
+            NodeKind::Block { statements } => statements,
+            _ => vec![ast],
+        };
+
+        Ok(statements
+            .into_iter()
+            .filter_map(|statement| match statement.kind {
+                NodeKind::Let { name, .. } => Some((name, statement.span)),
+                _ => None,
+            })
+            .collect())
+    }
+
     /// Excecutes the given source file by key.
     pub fn run(&mut self, key: DefaultKey) -> Result<Value> {
         let source = self.sources.get(key).expect("entry point does not exist");
-        let ast = source.parse(key)?;
+        let ast = source.parse(key, self.lexer_config, self.parser_config)?;
+
+        self.interpreter.run(ast)
+    }
+
+    /// Registers and runs a snippet, aborting with
+    /// [`RuntimeError::InstructionLimitExceeded`](crate::error::RuntimeError) once more
+    /// than `max_instructions` nodes have been evaluated.
+    ///
+    /// The interpreter is single threaded, so the budget stands in for a wall clock timeout.
+    pub fn eval_with_timeout(
+        &mut self,
+        name: String,
+        code: String,
+        max_instructions: usize,
+    ) -> Result<Value> {
+        let key = self.add_source(name, code);
 
-        let mut interpreter = Interpreter::new();
+        let capabilities = self.interpreter.capabilities;
+        self.interpreter.capabilities.max_instructions = Some(max_instructions);
 
-        interpreter.run(ast)
+        let result = self.run(key);
+        self.interpreter.capabilities = capabilities;
+
+        result
+    }
+
+    /// Returns the keywords and declared variables that could complete the given
+    /// identifier prefix. Word operators are only offered when the lexer accepts them.
+    pub fn completions(&self, prefix: &str) -> Vec<String> {
+        let keywords = Keyword::ALL
+            .iter()
+            .filter(|keyword| self.lexer_config.word_operators || keyword.as_operator().is_none())
+            .map(Keyword::to_string);
+
+        let mut variables: Vec<_> = self.interpreter.variables().map(str::to_string).collect();
+        variables.sort();
+
+        keywords
+            .chain(variables)
+            .filter(|name| name.starts_with(prefix))
+            .collect()
     }
 
     /// Pretty prints an error
-    pub fn pretty_print_error(&self, Error { span, kind }: Error) {
+    pub fn pretty_print_error(&self, error: Error) {
+        #[cfg(feature = "rich-diagnostics")]
+        eprint!("{}", self.render_error_rich(&error));
+
+        #[cfg(not(feature = "rich-diagnostics"))]
+        eprint!("{}", self.render_error(&error));
+    }
+
+    /// Renders an error with [`ariadne`], labelling the offending span.
+    ///
+    /// Errors without a source location fall back to [`Program::render_error`].
+    #[cfg(feature = "rich-diagnostics")]
+    pub fn render_error_rich(&self, error: &Error) -> String {
+        use ariadne::{sources, Color, Config, IndexType, Label, Report, ReportKind};
+
+        use crate::error::ErrorKind;
+
+        let Error { span, kind } = error;
+
+        if span.is_synthetic() {
+            return self.render_error(error);
+        }
+
+        let source = &self
+            .sources
+            .get(span.source)
+            .expect("registered source should be in sources");
+
+        let stage = match kind {
+            ErrorKind::Lexer(_) | ErrorKind::Parser(_) => "syntax error",
+            ErrorKind::Runtime(_) => "runtime error",
+        };
+
+        let mut output = Vec::new();
+
+        Report::build(ReportKind::Error, source.name.clone(), span.start)
+            .with_config(Config::default().with_index_type(IndexType::Byte))
+            .with_message(stage)
+            .with_label(
+                Label::new((source.name.clone(), span.as_range()))
+                    .with_message(kind)
+                    .with_color(Color::Red),
+            )
+            .finish()
+            .write(
+                sources([(source.name.clone(), source.content.as_str())]),
+                &mut output,
+            )
+            .expect("writing to a vector cannot fail");
+
+        String::from_utf8(output).expect("rendered report should be valid utf-8")
+    }
+
+    /// Renders an error, along with the offending source line, into a string.
+    pub fn render_error(&self, Error { span, kind }: &Error) -> String {
         use owo_colors::OwoColorize;
+        use std::fmt::Write;
+
+        if span.is_synthetic() {
+            return format!(
+                "{}: {}\n\n  {}\n",
+                "Error".red().bold(),
+                kind.bold(),
+                "<native>".black()
+            );
+        }
 
         let source = &self
             .sources
@@ -73,28 +272,73 @@ impl Program {
             None => 0,
         };
 
-        let line_end = source.content[span.end..]
+        let line_end = source.content[span.start..]
             .find('\n')
-            .map(|end| span.end + end)
+            .map(|end| span.start + end)
             .unwrap_or(source.content.len());
 
-        let line_number = source.content[..span.start].lines().count();
+        // a span crossing lines is only underlined up to the end of its first line,
+        // with one caret per character rather than per byte
+        let underlined = &source.content[span.start..span.end.min(line_end)];
+        let carets = "^".repeat(underlined.chars().count());
+        let continues = if span.end > line_end {
+            " ... (continues)"
+        } else {
+            ""
+        };
 
-        let at = format!("{} line {}:", source.name, line_number);
+        let (line_number, column) = span.line_col(source);
+        let padding = " ".repeat(column - 1);
 
-        let arrow_offset = 2 + at.len() + span.start - line_start;
+        let at = format!("{} line {line_number}, column {column}:", source.name);
 
-        eprintln!("{}: {}", "Error".red().bold(), kind.bold());
-        eprintln!();
+        let mut output = String::new();
 
-        eprint!("  {}", at.black());
+        writeln!(output, "{}: {}", "Error".red().bold(), kind.bold()).unwrap();
+        writeln!(output).unwrap();
 
-        eprintln!("  {}", &source.content[line_start..line_end]);
-        eprintln!(
-            "  {}{}",
-            " ".repeat(arrow_offset),
-            "^".repeat(span.end - span.start)
-        );
+        if !self.context_lines {
+            let arrow_offset = " ".repeat(2 + at.chars().count());
+
+            writeln!(
+                output,
+                "  {}  {}",
+                at.black(),
+                &source.content[line_start..line_end]
+            )
+            .unwrap();
+            writeln!(output, "  {arrow_offset}{padding}{carets}{continues}").unwrap();
+
+            return output;
+        }
+
+        let first = line_number.saturating_sub(1).max(1);
+        let last = line_number + 1;
+        let gutter_width = last.to_string().len();
+
+        writeln!(output, "  {}", at.black()).unwrap();
+
+        for number in first..=last {
+            let text = if number == line_number {
+                &source.content[line_start..line_end]
+            } else {
+                match source.line_text(number) {
+                    Some(text) => text,
+                    None => continue,
+                }
+            };
+
+            let gutter = format!("{number:>gutter_width$} |");
+            writeln!(output, "  {}  {}", gutter.black(), text).unwrap();
+
+            if number == line_number {
+                let gutter = format!("{:>gutter_width$} |", "");
+
+                writeln!(output, "  {}  {padding}{carets}{continues}", gutter.black()).unwrap();
+            }
+        }
+
+        output
     }
 }
 
@@ -103,3 +347,327 @@ impl Default for Program {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        ast::NodeKind,
+        error::{ErrorKind, ParserError, RuntimeError},
+        token::{Operator, Span, TokenKind, UnaryOperator},
+        value::ValueKind,
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_render_error_context_lines() {
+        let mut program = Program::new();
+        program.set_context_lines(true);
+
+        let key = program.add_source("<test>".to_string(), "1 +\n2 + $\n+ 3\n4".to_string());
+        let error = program.run(key).unwrap_err();
+        let rendered = program.render_error(&error);
+
+        let has_line = |gutter: &str, text: &str| {
+            rendered
+                .lines()
+                .any(|line| line.contains(gutter) && line.ends_with(text))
+        };
+
+        assert!(rendered.contains("<test> line 2, column 5:"));
+        assert!(has_line("1 |", "1 +"));
+        assert!(has_line("2 |", "2 + $"));
+        assert!(has_line("3 |", "+ 3"));
+        assert!(!rendered.contains("4 |"));
+    }
+
+    #[test]
+    fn test_render_error_multibyte_line() {
+        let mut program = Program::new();
+
+        // `1 - "λλ"` is 8 characters but 10 bytes, and starts after the 2 byte `é`
+        let key = program.add_source("<test>".to_string(), r#""é" + (1 - "λλ")"#.to_string());
+        let error = program.run(key).unwrap_err();
+
+        // the spaces and carets ending the line that underlines the error
+        let underline = |rendered: &str| {
+            let line = rendered.lines().find(|line| line.ends_with('^')).unwrap();
+            let before = line.trim_end_matches('^');
+            let spaces = before.len() - before.trim_end_matches(' ').len();
+
+            (spaces, line.len() - before.len())
+        };
+
+        let at = "<test> line 1, column 8:";
+        assert!(program.render_error(&error).contains(at));
+        assert_eq!(
+            underline(&program.render_error(&error)),
+            (2 + at.len() + 2 + 7, 8)
+        );
+
+        program.set_context_lines(true);
+        assert_eq!(underline(&program.render_error(&error)), (2 + 7, 8));
+    }
+
+    #[test]
+    fn test_render_end_of_file_after_multibyte() {
+        let mut program = Program::new();
+
+        let key = program.add_source("<test>".to_string(), "let λ".to_string());
+        let error = program.run(key).unwrap_err();
+
+        assert!(matches!(
+            error.kind,
+            ErrorKind::Parser(ParserError::UnexpectedEndOfFile)
+        ));
+        assert_eq!(error.span, Span::new(4..6, key));
+        assert!(program.render_error(&error).contains("line 1, column 5:"));
+    }
+
+    #[test]
+    fn test_eval_with_timeout() {
+        let mut program = Program::new();
+
+        let value = program
+            .eval_with_timeout("<test>".to_string(), "1 + 2".to_string(), 3)
+            .unwrap();
+        assert_eq!(value.kind, ValueKind::Integer(3));
+
+        let code = vec!["1"; 100].join(" + ");
+        let error = program
+            .eval_with_timeout("<test>".to_string(), code, 50)
+            .unwrap_err();
+
+        assert!(matches!(
+            error.kind,
+            ErrorKind::Runtime(RuntimeError::InstructionLimitExceeded)
+        ));
+        assert_eq!(program.interpreter.capabilities.max_instructions, None);
+
+        // an infinite loop is cut off by the budget
+        let error = program
+            .eval_with_timeout("<test>".to_string(), "while true { }".to_string(), 50)
+            .unwrap_err();
+
+        assert!(matches!(
+            error.kind,
+            ErrorKind::Runtime(RuntimeError::InstructionLimitExceeded)
+        ));
+    }
+
+    #[test]
+    fn test_render_error_synthetic_span() {
+        let program = Program::new();
+
+        let error = i64::try_from(Value::from(true)).unwrap_err();
+        let rendered = program.render_error(&error);
+
+        assert!(rendered.contains("<native>"));
+    }
+
+    #[test]
+    fn test_tokens_and_ast() {
+        let mut program = Program::new();
+        let key = program.add_source("<test>".to_string(), "1 + 2".to_string());
+
+        let tokens = program.tokens(key).unwrap();
+        let spans = tokens
+            .iter()
+            .map(|token| (token.span.start, token.span.end))
+            .collect::<Vec<_>>();
+        let kinds = tokens
+            .into_iter()
+            .map(|token| token.kind)
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            kinds,
+            vec![
+                TokenKind::Integer(1),
+                TokenKind::Operator(Operator::Plus),
+                TokenKind::Integer(2),
+            ]
+        );
+        assert_eq!(spans, vec![(0, 1), (2, 3), (4, 5)]);
+
+        let ast = program.ast(key).unwrap();
+
+        assert_eq!(ast.span, Span::new(0..5, key));
+        assert!(matches!(
+            ast.kind,
+            NodeKind::BinaryOp {
+                operator: Operator::Plus,
+                ..
+            }
+        ));
+
+        let key = program.add_source("<test>".to_string(), "-20".to_string());
+
+        let Ok(ASTNode {
+            kind: NodeKind::UnaryOp { operator, operand },
+            ..
+        }) = program.ast(key)
+        else {
+            panic!();
+        };
+
+        assert_eq!(operator, UnaryOperator::Minus);
+        assert_eq!(operand.kind, NodeKind::Integer(20));
+    }
+
+    #[test]
+    fn test_definitions() {
+        let mut program = Program::new();
+
+        let source = "let x = 1\nif true { let y = 2 }\nlet z = x";
+        let key = program.add_source("<test>".to_string(), source.to_string());
+
+        let definitions: Vec<_> = program
+            .definitions(key)
+            .unwrap()
+            .into_iter()
+            .map(|(name, span)| (name, &source[span.start..span.end]))
+            .collect();
+
+        assert_eq!(
+            definitions,
+            vec![
+                ("x".to_string(), "let x = 1"),
+                ("z".to_string(), "let z = x"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_registered_operator_result_in_expression() {
+        let mut program = Program::new();
+
+        // the handler's value has a synthetic span, which must not leak into
+        // spans merged with ones from the source
+        program
+            .operators_mut()
+            .register(Operator::Minus, "string", "string", |_, _| {
+                Ok(Value::from(7))
+            });
+
+        let key = program.add_source("<test>".to_string(), r#"("a" - "b") + 1"#.to_string());
+        let value = program.run(key).unwrap();
+
+        assert_eq!(value.kind, ValueKind::Integer(8));
+        assert_eq!(value.span, Span::new(1..15, key));
+    }
+
+    #[test]
+    fn test_registered_operator_mixing_host_values() {
+        let mut program = Program::new();
+
+        // the handler combines a script value with one built by the host
+        program
+            .operators_mut()
+            .register(Operator::Minus, "string", "integer", |_, rhs| {
+                rhs.add(&Value::from(1), ArithmeticMode::default())
+            });
+
+        let key = program.add_source("<test>".to_string(), r#""a" - 2"#.to_string());
+        let value = program.run(key).unwrap();
+
+        assert_eq!(value.kind, ValueKind::Integer(3));
+    }
+
+    #[test]
+    fn test_completions() {
+        let mut program = Program::new();
+
+        let key = program.add_source("<test>".to_string(), "let notes = 1".to_string());
+        program.run(key).unwrap();
+
+        // `not` is an ordinary identifier unless word operators are enabled
+        assert_eq!(program.completions("no"), vec!["notes"]);
+
+        program.set_lexer_config(LexerConfig {
+            word_operators: true,
+        });
+        assert_eq!(program.completions("no"), vec!["not", "notes"]);
+    }
+
+    #[test]
+    fn test_runs_share_variables() {
+        let mut program = Program::new();
+
+        let first = program.add_source("<test>".to_string(), "let x = 1".to_string());
+        let second = program.add_source("<test>".to_string(), "x + 1".to_string());
+
+        program.run(first).unwrap();
+        assert_eq!(program.run(second).unwrap().kind, ValueKind::Integer(2));
+
+        program.reset_interpreter();
+        assert!(program.run(second).is_err());
+    }
+
+    #[test]
+    fn test_render_multiline_span() {
+        let mut program = Program::new();
+
+        let key = program.add_source("<test>".to_string(), "1 + (2\n* 3)".to_string());
+        let error = Error {
+            span: Span::new(4..11, key),
+            kind: RuntimeError::IntegerOverflow.into(),
+        };
+
+        let rendered = program.render_error(&error);
+        let lines: Vec<_> = rendered.lines().collect();
+
+        assert!(lines.iter().any(|line| line.ends_with("1 + (2")));
+        assert!(!rendered.contains("* 3"));
+        assert!(lines
+            .iter()
+            .any(|line| line.ends_with(" ^^ ... (continues)")));
+
+        // single line spans are underlined exactly
+        let error = Error {
+            span: Span::new(4..6, key),
+            kind: RuntimeError::IntegerOverflow.into(),
+        };
+
+        let rendered = program.render_error(&error);
+        assert!(rendered.lines().any(|line| line.ends_with(" ^^")));
+        assert!(!rendered.contains("continues"));
+    }
+
+    #[test]
+    #[cfg(feature = "rich-diagnostics")]
+    fn test_render_error_rich() {
+        let mut program = Program::new();
+        let key = program.add_source("script.hx".to_string(), "let x = 1\n\"a\" - x".to_string());
+
+        let error = program.run(key).unwrap_err();
+        let rendered = program.render_error_rich(&error);
+
+        assert!(rendered.contains("script.hx"));
+        assert!(rendered.contains("runtime error"));
+        assert!(rendered.contains(&error.kind.to_string()));
+    }
+
+    #[test]
+    fn test_render_error_line_numbers() {
+        let mut program = Program::new();
+
+        let key = program.add_source("<test>".to_string(), "$\n1 + $\n\n$".to_string());
+
+        let at = |start: usize| {
+            let error = Error {
+                span: Span::new(start..start + 1, key),
+                kind: RuntimeError::IntegerOverflow.into(),
+            };
+
+            program.render_error(&error)
+        };
+
+        assert!(at(0).contains("<test> line 1, column 1:"));
+        assert!(at(6).contains("<test> line 2, column 5:"));
+        assert!(at(9).contains("<test> line 4, column 1:"));
+
+        // a span starting at a newline belongs to the line that newline ends
+        assert!(at(1).contains("<test> line 1, column 2:"));
+    }
+}
@@ -1,5 +1,5 @@
-use crate::program::Source;
-use slotmap::DefaultKey;
+use crate::{cursor::Width, program::Source};
+use slotmap::{DefaultKey, Key};
 use std::{
     fmt::{Display, Write},
     ops::Range,
@@ -36,8 +36,18 @@ pub enum TokenKind {
     /// A type of parenthesis.
     Parenthesis(Parenthesis),
 
-    /// Any form of whitespace (spaces, tabs, newlines).
-    /// Only used for lexing, and is discarded by the lexer.
+    /// A comma (`,`).
+    Comma,
+
+    /// A line break, which separates statements.
+    Newline,
+
+    /// A semicolon (`;`), which separates statements like a line break.
+    Semicolon,
+
+    /// Whitespace other than line breaks, which are [`TokenKind::Newline`], and
+    /// block comments that stay on one line. Only used for lexing, and is
+    /// discarded by the lexer.
     Whitespace,
 }
 
@@ -48,10 +58,38 @@ pub enum Keyword {
     True,
     /// The `false` literal
     False,
+    /// The `nan` literal, a floating point NaN
+    Nan,
+    /// The `inf` literal, floating point positive infinity
+    Inf,
+    /// The `null` literal, the absence of a value
+    Null,
+
+    /// The `and` word operator, an alias for `&&`
+    And,
+    /// The `or` word operator, an alias for `||`
+    Or,
+    /// The `not` word operator, an alias for `!`
+    Not,
+
+    /// The `let` keyword, which declares a variable
+    Let,
+    /// The `if` keyword
+    If,
+    /// The `else` keyword
+    Else,
+    /// The `while` keyword
+    While,
+    /// The `break` keyword
+    Break,
+    /// The `continue` keyword
+    Continue,
+    /// The `print` keyword, which writes a value to the output
+    Print,
 }
 
 /// An operator in the source code.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum Operator {
     /// The plus operator (`+`)
     Plus,
@@ -61,6 +99,10 @@ pub enum Operator {
     Multiply,
     /// The division operator (`/`)
     Divide,
+    /// The remainder operator (`%`)
+    Modulo,
+    /// The exponentiation operator (`**`)
+    Power,
 
     /// The assignment operator (`=`)
     Assign,
@@ -100,6 +142,8 @@ pub enum UnaryOperator {
     Minus,
     /// The not unary operator (`!`)
     Not,
+    /// The postfix factorial operator (`!`)
+    Factorial,
 }
 
 /// A type of parenthesis in the source code.
@@ -116,6 +160,10 @@ pub struct Parenthesis {
 pub enum ParenthesisKind {
     /// A round parenthesis (`(`, `)`)
     Round,
+    /// A square bracket (`[`, `]`)
+    Square,
+    /// A curly brace (`{`, `}`)
+    Curly,
 }
 
 /// Whether a parenthesis is an opening or closing parenthesis.
@@ -144,6 +192,18 @@ impl Token {
     pub const fn new(kind: TokenKind, span: Span) -> Self {
         Self { kind, span }
     }
+
+    /// Formats the token's kind along with its range, e.g. `Integer(42) @ 0..2`.
+    pub fn debug_display(&self) -> String {
+        format!("{:?} @ {}..{}", self.kind, self.span.start, self.span.end)
+    }
+}
+
+/// Tokens are counted individually, so a parser's position is a token index.
+impl Width for Token {
+    fn width(&self) -> usize {
+        1
+    }
 }
 
 impl Span {
@@ -153,6 +213,64 @@ impl Span {
 
         Self { start, end, source }
     }
+
+    /// Creates a span for values and nodes that don't originate from any source,
+    /// such as values constructed by the host.
+    pub fn synthetic() -> Self {
+        Self::new(0..0, DefaultKey::null())
+    }
+
+    /// Returns whether this span was created by [`Span::synthetic`].
+    pub fn is_synthetic(&self) -> bool {
+        self.source.is_null()
+    }
+
+    /// Returns the byte range this span covers within its source.
+    pub const fn as_range(&self) -> Range<usize> {
+        self.start..self.end
+    }
+
+    /// Returns the 1-based line and column at which this span starts in `source`.
+    ///
+    /// Columns count characters, so a tab is a single column.
+    // TODO: expand tabs to the next tab stop once the tab width is configurable.
+    pub fn line_col(&self, source: &Source) -> (usize, usize) {
+        let before = &source.content[..self.start];
+
+        let line = before.matches('\n').count() + 1;
+        let line_start = before.rfind('\n').map_or(0, |newline| newline + 1);
+        let column = before[line_start..].chars().count() + 1;
+
+        (line, column)
+    }
+
+    /// Creates a span covering both `self` and `other`.
+    ///
+    /// Both spans must belong to the same source; merging spans across
+    /// sources is a logic error and panics in debug builds. The exception is a
+    /// [synthetic](Span::synthetic) span, which has no location, so merging it
+    /// with a span from a source returns the latter.
+    pub fn merge(self, other: Span) -> Self {
+        if self.source != other.source {
+            if self.is_synthetic() {
+                return other;
+            }
+
+            if other.is_synthetic() {
+                return self;
+            }
+        }
+
+        debug_assert_eq!(
+            self.source, other.source,
+            "attempted to merge spans from different sources"
+        );
+
+        Self::new(
+            self.start.min(other.start)..self.end.max(other.end),
+            self.source,
+        )
+    }
 }
 
 impl Operator {
@@ -160,8 +278,10 @@ impl Operator {
         Some(match (a, b) {
             ('+', _) => Self::Plus,
             ('-', _) => Self::Minus,
+            ('*', Some('*')) => Self::Power,
             ('*', _) => Self::Multiply,
             ('/', _) => Self::Divide,
+            ('%', _) => Self::Modulo,
 
             ('=', Some('=')) => Self::Equals,
             ('!', Some('=')) => Self::NotEquals,
@@ -185,7 +305,8 @@ impl Operator {
     pub fn is_two_char(&self) -> bool {
         matches!(
             self,
-            Self::Equals
+            Self::Power
+                | Self::Equals
                 | Self::NotEquals
                 | Self::LessThanEquals
                 | Self::GreaterThanEquals
@@ -219,6 +340,8 @@ impl Parenthesis {
     pub fn from_char(c: char) -> Option<Self> {
         let kind = match c {
             '(' | ')' => ParenthesisKind::Round,
+            '[' | ']' => ParenthesisKind::Square,
+            '{' | '}' => ParenthesisKind::Curly,
             _ => return None,
         };
 
@@ -233,15 +356,57 @@ impl Parenthesis {
     }
 
     fn is_opening(c: char) -> bool {
-        matches!(c, '(')
+        matches!(c, '(' | '[' | '{')
     }
 }
 
 impl Keyword {
+    /// Every keyword in the language.
+    pub const ALL: &'static [Keyword] = &[
+        Self::True,
+        Self::False,
+        Self::Nan,
+        Self::Inf,
+        Self::Null,
+        Self::And,
+        Self::Or,
+        Self::Not,
+        Self::Let,
+        Self::If,
+        Self::Else,
+        Self::While,
+        Self::Break,
+        Self::Continue,
+        Self::Print,
+    ];
+
     pub fn from_ident(ident: &str) -> Option<Self> {
         Some(match ident {
             "true" => Self::True,
             "false" => Self::False,
+            "nan" => Self::Nan,
+            "inf" => Self::Inf,
+            "null" => Self::Null,
+            "and" => Self::And,
+            "or" => Self::Or,
+            "not" => Self::Not,
+            "let" => Self::Let,
+            "if" => Self::If,
+            "else" => Self::Else,
+            "while" => Self::While,
+            "break" => Self::Break,
+            "continue" => Self::Continue,
+            "print" => Self::Print,
+            _ => return None,
+        })
+    }
+
+    /// Returns the operator this keyword is an alias for, if any.
+    pub fn as_operator(&self) -> Option<Operator> {
+        Some(match self {
+            Self::And => Operator::And,
+            Self::Or => Operator::Or,
+            Self::Not => Operator::Not,
             _ => return None,
         })
     }
@@ -254,6 +419,8 @@ impl Display for Operator {
             Self::Minus => "-",
             Self::Multiply => "*",
             Self::Divide => "/",
+            Self::Modulo => "%",
+            Self::Power => "**",
             Self::Assign => "=",
             Self::Equals => "==",
             Self::NotEquals => "!=",
@@ -273,6 +440,19 @@ impl Display for Keyword {
         f.write_str(match self {
             Self::True => "true",
             Self::False => "false",
+            Self::Nan => "nan",
+            Self::Inf => "inf",
+            Self::Null => "null",
+            Self::And => "and",
+            Self::Or => "or",
+            Self::Not => "not",
+            Self::Let => "let",
+            Self::If => "if",
+            Self::Else => "else",
+            Self::While => "while",
+            Self::Break => "break",
+            Self::Continue => "continue",
+            Self::Print => "print",
         })
     }
 }
@@ -285,6 +465,10 @@ impl Display for Parenthesis {
         f.write_char(match (self.kind, self.opening) {
             (PK::Round, O::Open) => '(',
             (PK::Round, O::Close) => ')',
+            (PK::Square, O::Open) => '[',
+            (PK::Square, O::Close) => ']',
+            (PK::Curly, O::Open) => '{',
+            (PK::Curly, O::Close) => '}',
         })
     }
 }
@@ -305,16 +489,25 @@ impl Display for TokenKind {
             Self::Operator(op) => op.to_string(),
             Self::Keyword(keyword) => keyword.to_string(),
             Self::Parenthesis(parenthesis) => parenthesis.to_string(),
+            Self::Comma => ",".to_string(),
+            Self::Newline => "<newline>".to_string(),
+            Self::Semicolon => ";".to_string(),
             Self::Whitespace => "<whitespace>".to_string(),
         })
     }
 }
 
+impl From<Span> for Range<usize> {
+    fn from(span: Span) -> Self {
+        span.as_range()
+    }
+}
+
 impl std::ops::Index<Span> for Source {
     type Output = str;
 
     fn index(&self, index: Span) -> &Self::Output {
-        &self.content[index.start..index.end]
+        &self.content[index.as_range()]
     }
 }
 
@@ -327,7 +520,7 @@ impl TokenExt for char {
     fn is_operator_start(&self) -> bool {
         matches!(
             self,
-            '=' | '!' | '<' | '>' | '+' | '-' | '*' | '/' | '&' | '|'
+            '=' | '!' | '<' | '>' | '+' | '-' | '*' | '/' | '%' | '&' | '|'
         )
     }
 
@@ -335,3 +528,77 @@ impl TokenExt for char {
         Parenthesis::from_char(*self).is_some()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use slotmap::SlotMap;
+
+    use super::*;
+
+    #[test]
+    fn test_span_merge() {
+        let mut keys = SlotMap::new();
+        let source = keys.insert(());
+
+        let merged = Span::new(4..7, source).merge(Span::new(0..2, source));
+
+        assert_eq!(merged, Span::new(0..7, source));
+    }
+
+    #[test]
+    fn test_span_merge_synthetic() {
+        let mut keys = SlotMap::new();
+        let span = Span::new(2..5, keys.insert(()));
+
+        assert_eq!(Span::synthetic().merge(span), span);
+        assert_eq!(span.merge(Span::synthetic()), span);
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "different sources")]
+    fn test_span_merge_across_sources() {
+        let mut keys = SlotMap::new();
+        let (a, b) = (keys.insert(()), keys.insert(()));
+
+        let _ = Span::new(0..1, a).merge(Span::new(2..3, b));
+    }
+
+    #[test]
+    fn test_span_range_round_trip() {
+        let mut keys = SlotMap::new();
+        let source = keys.insert(());
+
+        let span = Span::new(3..8, source);
+        let range: Range<usize> = span.into();
+
+        assert_eq!(range, 3..8);
+        assert_eq!(Span::new(range, span.source), span);
+    }
+
+    #[test]
+    fn test_token_debug_display() {
+        let token = Token::new(TokenKind::Integer(42), Span::new(0..2, DefaultKey::null()));
+
+        assert_eq!(token.debug_display(), "Integer(42) @ 0..2");
+        assert_eq!(token.to_string(), "42");
+    }
+
+    #[test]
+    fn test_line_col() {
+        let source = Source {
+            name: "<test>".to_string(),
+            content: "ab\n\tλx\n".to_string(),
+        };
+
+        let line_col = |start| Span::new(start..start, DefaultKey::null()).line_col(&source);
+
+        assert_eq!(line_col(0), (1, 1));
+        assert_eq!(line_col(1), (1, 2));
+        assert_eq!(line_col(2), (1, 3));
+        assert_eq!(line_col(3), (2, 1));
+        // the tab and the two byte `λ` are one column each
+        assert_eq!(line_col(6), (2, 3));
+        assert_eq!(line_col(8), (3, 1));
+    }
+}
@@ -1,40 +1,52 @@
-use std::fmt::Display;
+use std::{
+    collections::HashMap,
+    fmt::Display,
+    panic::{self, AssertUnwindSafe},
+};
 
-use crate::{error::Result, token::Span};
+use crate::{
+    error::{Error, Result, RuntimeError},
+    token::{Operator, Span},
+};
 
 macro_rules! impl_binary_operator {
     (
-        $( ($name:ident, $operator:ident, {
+        $( ($name:ident, $operator:ident, $(|$mode:ident|)? {
             $( ($lhs:pat, $rhs:pat) => $body:expr),*
         }) ),*
 
     ) => {
         $(
             impl Value {
-                pub fn $name(&self, other: &Value) -> $crate::error::Result<Value> {
+                pub fn $name(
+                    &self,
+                    other: &Value
+                    $(, $mode: $crate::value::ArithmeticMode)?
+                ) -> $crate::error::Result<Value> {
                     use $crate::value::ValueKind::*;
+                    use $crate::value::OperatorResult;
                     use $crate::token::Operator::*;
 
-                    let span = Span::new(self.span.start..other.span.end, self.span.source);
+                    let span = self.span.merge(other.span);
 
                     let kind = match (&self.kind, &other.kind) {
                         $( ($lhs, $rhs) => {
-                            $body
+                            $body.into_operator_result()
                         })*
-                        _ => return Err($crate::error::Error {
-                            span,
-                            kind: $crate::error::RuntimeError::InvalidBinaryOperation {
-                                lhs: self.kind.clone(),
-                                rhs: other.kind.clone(),
-                                operator: $operator
-                            }.into()
+                        _ => Err($crate::error::RuntimeError::InvalidBinaryOperation {
+                            lhs: self.kind.clone(),
+                            rhs: other.kind.clone(),
+                            operator: $operator
                         }),
                     };
 
-                    Ok($crate::value::Value {
-                        kind,
-                        span
-                    })
+                    match kind {
+                        Ok(kind) => Ok($crate::value::Value { kind, span }),
+                        Err(kind) => Err($crate::error::Error {
+                            span,
+                            kind: kind.into(),
+                        }),
+                    }
                 }
             }
         )*
@@ -43,42 +55,78 @@ macro_rules! impl_binary_operator {
 
 macro_rules! impl_unary_operator {
     (
-        $( ($name:ident, $operator:ident, {
+        $( ($name:ident, $operator:ident, $(|$mode:ident|)? {
             $( $operand:pat => $body:expr),*
         }) ),*
     ) => {
         impl Value {
             $(
-                pub fn $name(&self) -> $crate::error::Result<Value> {
+                pub fn $name(
+                    &self
+                    $(, $mode: $crate::value::ArithmeticMode)?
+                ) -> $crate::error::Result<Value> {
                     use $crate::value::ValueKind::*;
+                    use $crate::value::OperatorResult;
                     use $crate::token::Operator::*;
 
                     let span = self.span.clone();
 
                     let kind = match &self.kind {
                         $( $operand  => {
-                            $body
+                            $body.into_operator_result()
                         })*
 
-                        _ => return Err($crate::error::Error {
-                            span,
-                            kind: $crate::error::RuntimeError::InvalidUnaryOperation {
-                                operand: self.kind.clone(),
-                                operator: $operator
-                            }.into()
+                        _ => Err($crate::error::RuntimeError::InvalidUnaryOperation {
+                            operand: self.kind.clone(),
+                            operator: $operator
                         }),
                     };
 
-                    Ok($crate::value::Value {
-                        kind,
-                        span
-                    })
+                    match kind {
+                        Ok(kind) => Ok($crate::value::Value { kind, span }),
+                        Err(kind) => Err($crate::error::Error {
+                            span,
+                            kind: kind.into(),
+                        }),
+                    }
                 }
             )*
         }
     };
 }
 
+macro_rules! impl_value_conversion {
+    (
+        $( ($type:ty, $kind:ident, $name:literal) ),*
+    ) => {
+        $(
+            impl From<$type> for Value {
+                fn from(value: $type) -> Self {
+                    Value::new(ValueKind::$kind(value), Span::synthetic())
+                }
+            }
+
+            impl TryFrom<Value> for $type {
+                type Error = $crate::error::Error;
+
+                fn try_from(value: Value) -> $crate::error::Result<Self> {
+                    match value.kind {
+                        ValueKind::$kind(inner) => Ok(inner),
+                        found => Err($crate::error::Error {
+                            span: value.span,
+                            kind: RuntimeError::UnexpectedValueKind {
+                                expected: $name,
+                                found,
+                            }
+                            .into(),
+                        }),
+                    }
+                }
+            }
+        )*
+    };
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct Value {
     pub kind: ValueKind,
@@ -95,6 +143,152 @@ pub enum ValueKind {
     Boolean(bool),
     /// A string.
     String(String),
+    /// A fixed-size list of values of any kind.
+    Tuple(Vec<Value>),
+    /// A list of values of any kind.
+    Array(Vec<Value>),
+    /// The absence of a value.
+    Null,
+}
+
+/// The type of a [`ValueKind`], without its contents.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ValueType {
+    /// The type of [`ValueKind::Float`].
+    Float,
+    /// The type of [`ValueKind::Integer`].
+    Integer,
+    /// The type of [`ValueKind::Boolean`].
+    Boolean,
+    /// The type of [`ValueKind::String`].
+    String,
+    /// The type of [`ValueKind::Tuple`].
+    Tuple,
+    /// The type of [`ValueKind::Array`].
+    Array,
+    /// The type of [`ValueKind::Null`].
+    Null,
+}
+
+/// A handler for a binary operator applied to a specific pair of value kinds.
+pub type BinaryHandler = Box<dyn Fn(&Value, &Value) -> Result<Value>>;
+
+/// A table of binary operator handlers keyed by the operator and the names
+/// of the operand kinds (see [`ValueKind::name`]).
+///
+/// Handlers registered here take precedence over the built in operators, which
+/// allows adding new type combinations without touching the operator macros.
+#[derive(Default)]
+pub struct OperatorRegistry {
+    handlers: HashMap<(Operator, &'static str, &'static str), BinaryHandler>,
+}
+
+/// How integer arithmetic behaves when a result does not fit in an `i64`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ArithmeticMode {
+    /// Overflowing raises a [`RuntimeError::IntegerOverflow`].
+    #[default]
+    Checked,
+    /// Overflowing wraps around at the bounds of an `i64`.
+    Wrapping,
+    /// Overflowing clamps the result to `i64::MIN` or `i64::MAX`.
+    Saturating,
+}
+
+/// The result of a single arm of a binary operator, allowing arms to either
+/// produce a value directly or fail with a [`RuntimeError`].
+trait OperatorResult {
+    fn into_operator_result(self) -> std::result::Result<ValueKind, RuntimeError>;
+}
+
+impl OperatorResult for ValueKind {
+    fn into_operator_result(self) -> std::result::Result<ValueKind, RuntimeError> {
+        Ok(self)
+    }
+}
+
+impl OperatorResult for std::result::Result<ValueKind, RuntimeError> {
+    fn into_operator_result(self) -> std::result::Result<ValueKind, RuntimeError> {
+        self
+    }
+}
+
+impl ArithmeticMode {
+    /// Adds two integers according to this mode.
+    pub fn add(self, a: i64, b: i64) -> std::result::Result<i64, RuntimeError> {
+        match self {
+            Self::Checked => a.checked_add(b).ok_or(RuntimeError::IntegerOverflow),
+            Self::Wrapping => Ok(a.wrapping_add(b)),
+            Self::Saturating => Ok(a.saturating_add(b)),
+        }
+    }
+
+    /// Subtracts two integers according to this mode.
+    pub fn subtract(self, a: i64, b: i64) -> std::result::Result<i64, RuntimeError> {
+        match self {
+            Self::Checked => a.checked_sub(b).ok_or(RuntimeError::IntegerOverflow),
+            Self::Wrapping => Ok(a.wrapping_sub(b)),
+            Self::Saturating => Ok(a.saturating_sub(b)),
+        }
+    }
+
+    /// Multiplies two integers according to this mode.
+    pub fn multiply(self, a: i64, b: i64) -> std::result::Result<i64, RuntimeError> {
+        match self {
+            Self::Checked => a.checked_mul(b).ok_or(RuntimeError::IntegerOverflow),
+            Self::Wrapping => Ok(a.wrapping_mul(b)),
+            Self::Saturating => Ok(a.saturating_mul(b)),
+        }
+    }
+
+    /// Negates an integer according to this mode.
+    pub fn negate(self, a: i64) -> std::result::Result<i64, RuntimeError> {
+        match self {
+            Self::Checked => a.checked_neg().ok_or(RuntimeError::IntegerOverflow),
+            Self::Wrapping => Ok(a.wrapping_neg()),
+            Self::Saturating => Ok(a.saturating_neg()),
+        }
+    }
+
+    /// Raises an integer to a non-negative power according to this mode.
+    pub fn power(self, base: i64, exponent: u64) -> std::result::Result<i64, RuntimeError> {
+        // these never overflow, however large the exponent
+        match base {
+            0 => return Ok(if exponent == 0 { 1 } else { 0 }),
+            1 => return Ok(1),
+            -1 => return Ok(if exponent.is_multiple_of(2) { 1 } else { -1 }),
+            _ => {}
+        }
+
+        // any other base overflows long before the exponent stops fitting in a `u32`
+        let small = u32::try_from(exponent);
+
+        match self {
+            Self::Checked => small
+                .ok()
+                .and_then(|exponent| base.checked_pow(exponent))
+                .ok_or(RuntimeError::IntegerOverflow),
+            Self::Wrapping => {
+                let (mut result, mut base, mut exponent) = (1i64, base, exponent);
+
+                while exponent > 0 {
+                    if exponent % 2 == 1 {
+                        result = result.wrapping_mul(base);
+                    }
+
+                    base = base.wrapping_mul(base);
+                    exponent /= 2;
+                }
+
+                Ok(result)
+            }
+            Self::Saturating => Ok(match small {
+                Ok(exponent) => base.saturating_pow(exponent),
+                Err(_) if base < 0 && exponent % 2 == 1 => i64::MIN,
+                Err(_) => i64::MAX,
+            }),
+        }
+    }
 }
 
 impl Value {
@@ -102,58 +296,121 @@ impl Value {
     pub fn new(kind: ValueKind, span: Span) -> Self {
         Self { kind, span }
     }
+
+    /// Formats the value annotated with its type, e.g. `42 : integer`.
+    pub fn display_debug(&self) -> String {
+        match &self.kind {
+            ValueKind::String(s) => format!("{s:?} : {}", self.kind.name()),
+            kind => format!("{kind} : {}", kind.name()),
+        }
+    }
 }
 
+// The operand kinds accepted by each binary operator. Any combination not
+// listed results in an `InvalidBinaryOperation`. `test_coercion_matrix`
+// enforces this table.
+//
+// `num` is an integer or a float. When an integer meets a float it is promoted,
+// so mixed arithmetic always yields a float.
+//
+// Dividing an integer by zero is a `DivisionByZero` error, while float division
+// follows IEEE 754, so `1.0 / 0.0` is infinity and `0.0 / 0.0` is NaN. The same
+// holds for `%`, which takes the sign of the dividend (`-7 % 3 == -1`).
+//
+// Raising an integer to a negative integer power yields a float, since the
+// result is fractional.
+//
+//   +                  num + num, string + string
+//   -, /, %, **        num op num
+//   *                  num * num, int * string (repetition)
+//   <, <=, >, >=       num op num (yielding a boolean)
+//   ==, !=             any two values (yielding a boolean); values of different
+//                      kinds are unequal, except that numbers compare by value
+//   &&, ||             bool op bool
 impl_binary_operator! {
-    (add, Plus, {
+    (add, Plus, |mode| {
         (Float(a), Float(b)) => Float(a + b),
-        (Integer(a), Integer(b)) => Integer(a + b),
+        (Integer(a), Float(b)) => Float(*a as f64 + b),
+        (Float(a), Integer(b)) => Float(a + *b as f64),
+        (Integer(a), Integer(b)) => mode.add(*a, *b).map(Integer),
         (String(a), String(b)) => String(a.to_owned() + b)
     }),
 
-    (subtract, Minus, {
+    (subtract, Minus, |mode| {
         (Float(a), Float(b)) => Float(a - b),
-        (Integer(a), Integer(b)) => Integer(a - b)
+        (Integer(a), Float(b)) => Float(*a as f64 - b),
+        (Float(a), Integer(b)) => Float(a - *b as f64),
+        (Integer(a), Integer(b)) => mode.subtract(*a, *b).map(Integer)
     }),
 
-    (multiply, Multiply, {
+    (multiply, Multiply, |mode| {
         (Float(a), Float(b)) => Float(a * b),
-        (Integer(a), Integer(b)) => Integer(a * b),
-        (Integer(count), String(b)) => String(b.repeat(*count as usize))
+        (Integer(a), Float(b)) => Float(*a as f64 * b),
+        (Float(a), Integer(b)) => Float(a * *b as f64),
+        (Integer(a), Integer(b)) => mode.multiply(*a, *b).map(Integer),
+        (Integer(count), String(b)) => match usize::try_from(*count) {
+            Ok(count) => Ok(String(b.repeat(count))),
+            Err(_) => Err(RuntimeError::DomainError {
+                operation: "string repetition",
+                value: Integer(*count),
+            }),
+        }
     }),
 
     (divide, Divide, {
         (Float(a), Float(b)) => Float(a / b),
-        (Integer(a), Integer(b)) => Integer(a / b)
+        (Integer(a), Float(b)) => Float(*a as f64 / b),
+        (Float(a), Integer(b)) => Float(a / *b as f64),
+        (Integer(_), Integer(0)) => Err(RuntimeError::DivisionByZero),
+        (Integer(a), Integer(b)) => a.checked_div(*b).map(Integer).ok_or(RuntimeError::IntegerOverflow)
+    }),
+
+    (modulo, Modulo, {
+        (Float(a), Float(b)) => Float(a % b),
+        (Integer(a), Float(b)) => Float(*a as f64 % b),
+        (Float(a), Integer(b)) => Float(a % *b as f64),
+        (Integer(_), Integer(0)) => Err(RuntimeError::DivisionByZero),
+        (Integer(a), Integer(b)) => a.checked_rem(*b).map(Integer).ok_or(RuntimeError::IntegerOverflow)
+    }),
+
+    (power, Power, |mode| {
+        (Float(a), Float(b)) => Float(a.powf(*b)),
+        (Integer(a), Float(b)) => Float((*a as f64).powf(*b)),
+        (Float(a), Integer(b)) => Float(a.powf(*b as f64)),
+        (Integer(a), Integer(b)) => match u64::try_from(*b) {
+            Ok(b) => mode.power(*a, b).map(Integer),
+            Err(_) => Ok(Float((*a as f64).powf(*b as f64))),
+        }
     }),
 
     (less_than, LessThan, {
         (Float(a), Float(b)) => Boolean(a < b),
+        (Integer(a), Float(b)) => Boolean((*a as f64) < *b),
+        (Float(a), Integer(b)) => Boolean(*a < *b as f64),
         (Integer(a), Integer(b)) => Boolean(a < b)
     }),
 
     (less_than_or_equal, LessThanEquals, {
         (Float(a), Float(b)) => Boolean(a <= b),
+        (Integer(a), Float(b)) => Boolean((*a as f64) <= *b),
+        (Float(a), Integer(b)) => Boolean(*a <= *b as f64),
         (Integer(a), Integer(b)) => Boolean(a <= b)
     }),
 
     (greater_than, GreaterThan, {
         (Float(a), Float(b)) => Boolean(a > b),
+        (Integer(a), Float(b)) => Boolean((*a as f64) > *b),
+        (Float(a), Integer(b)) => Boolean(*a > *b as f64),
         (Integer(a), Integer(b)) => Boolean(a > b)
     }),
 
     (greater_than_or_equal, GreaterThanEquals, {
         (Float(a), Float(b)) => Boolean(a >= b),
+        (Integer(a), Float(b)) => Boolean((*a as f64) >= *b),
+        (Float(a), Integer(b)) => Boolean(*a >= *b as f64),
         (Integer(a), Integer(b)) => Boolean(a >= b)
     }),
 
-    (equal, Equals, {
-        (Float(a), Float(b)) => Boolean(a == b),
-        (Integer(a), Integer(b)) => Boolean(a == b),
-        (Boolean(a), Boolean(b)) => Boolean(a == b),
-        (String(a), String(b)) => Boolean(a == b)
-    }),
-
     (and, And, {
         (Boolean(a), Boolean(b)) => Boolean(*a && *b)
     }),
@@ -164,9 +421,36 @@ impl_binary_operator! {
 }
 
 impl Value {
+    pub fn equal(&self, other: &Value) -> Result<Value> {
+        Ok(Value::new(
+            ValueKind::Boolean(self.equals(other)),
+            self.span.merge(other.span),
+        ))
+    }
+
     pub fn not_equal(&self, other: &Value) -> Result<Value> {
         self.equal(other)?.not()
     }
+
+    /// Compares two values of any kinds, where values of different kinds are never
+    /// equal unless they are both numbers.
+    fn equals(&self, other: &Value) -> bool {
+        use ValueKind::*;
+
+        match (&self.kind, &other.kind) {
+            (Float(a), Float(b)) => a == b,
+            (Integer(a), Float(b)) => (*a as f64) == *b,
+            (Float(a), Integer(b)) => *a == *b as f64,
+            (Integer(a), Integer(b)) => a == b,
+            (Boolean(a), Boolean(b)) => a == b,
+            (String(a), String(b)) => a == b,
+            (Tuple(a), Tuple(b)) | (Array(a), Array(b)) => {
+                a.len() == b.len() && a.iter().zip(b).all(|(a, b)| a.equals(b))
+            }
+            (Null, Null) => true,
+            _ => false,
+        }
+    }
 }
 
 impl_unary_operator! {
@@ -174,20 +458,172 @@ impl_unary_operator! {
         Boolean(b) => Boolean(!b)
     }),
 
-    (negate, Minus, {
+    (negate, Minus, |mode| {
         Float(f) => Float(-f),
-        Integer(i) => Integer(-i)
+        Integer(i) => mode.negate(*i).map(Integer)
     })
 }
 
+impl Value {
+    /// Computes the factorial of a non-negative integer.
+    pub fn factorial(&self) -> Result<Value> {
+        let error = |kind: RuntimeError| Error {
+            span: self.span,
+            kind: kind.into(),
+        };
+
+        let n = match self.kind {
+            ValueKind::Integer(n) if n >= 0 => n,
+            _ => {
+                return Err(error(RuntimeError::DomainError {
+                    operation: "factorial",
+                    value: self.kind.clone(),
+                }))
+            }
+        };
+
+        let result = (1..=n)
+            .try_fold(1i64, |acc, i| acc.checked_mul(i))
+            .ok_or_else(|| error(RuntimeError::IntegerOverflow))?;
+
+        Ok(Value::new(ValueKind::Integer(result), self.span))
+    }
+}
+
+impl_value_conversion! {
+    (i64, Integer, "integer"),
+    (f64, Float, "float"),
+    (bool, Boolean, "boolean"),
+    (String, String, "string")
+}
+
+impl From<&str> for Value {
+    fn from(value: &str) -> Self {
+        Value::from(value.to_owned())
+    }
+}
+
+impl OperatorRegistry {
+    /// Registers a handler for `operator` applied between values of the given kinds,
+    /// replacing any previously registered handler.
+    ///
+    /// ```
+    /// use helix::{program::Program, Error, ErrorKind, Operator, RuntimeError, Value, ValueKind};
+    ///
+    /// let mut program = Program::new();
+    ///
+    /// // `"file.txt" - ".txt"` removes a suffix
+    /// program
+    ///     .operators_mut()
+    ///     .register(Operator::Minus, "string", "string", |lhs, rhs| {
+    ///         let (ValueKind::String(a), ValueKind::String(b)) = (&lhs.kind, &rhs.kind) else {
+    ///             unreachable!("only registered for strings");
+    ///         };
+    ///
+    ///         match a.strip_suffix(b.as_str()) {
+    ///             Some(stem) => Ok(Value::new(ValueKind::String(stem.to_string()), lhs.span)),
+    ///             None => Err(Error {
+    ///                 span: rhs.span,
+    ///                 kind: RuntimeError::InvalidBinaryOperation {
+    ///                     lhs: lhs.kind.clone(),
+    ///                     operator: Operator::Minus,
+    ///                     rhs: rhs.kind.clone(),
+    ///                 }
+    ///                 .into(),
+    ///             }),
+    ///         }
+    ///     });
+    ///
+    /// let key = program.add_source("<doc>".to_string(), r#""file.txt" - ".txt""#.to_string());
+    /// assert_eq!(program.run(key).unwrap().kind, ValueKind::String("file".to_string()));
+    ///
+    /// let key = program.add_source("<doc>".to_string(), r#""file.txt" - ".md""#.to_string());
+    /// assert!(matches!(
+    ///     program.run(key).unwrap_err().kind,
+    ///     ErrorKind::Runtime(RuntimeError::InvalidBinaryOperation { .. })
+    /// ));
+    /// ```
+    pub fn register<F>(
+        &mut self,
+        operator: Operator,
+        lhs: &'static str,
+        rhs: &'static str,
+        handler: F,
+    ) where
+        F: Fn(&Value, &Value) -> Result<Value> + 'static,
+    {
+        self.handlers
+            .insert((operator, lhs, rhs), Box::new(handler));
+    }
+
+    /// Applies the handler registered for `operator` between `lhs` and `rhs`, if any.
+    ///
+    /// The result spans both operands, since handlers often build values with
+    /// [`Span::synthetic`]. A panicking handler is caught and reported as a
+    /// [`RuntimeError::HostFunctionPanicked`] rather than aborting the interpreter.
+    pub fn apply(&self, lhs: &Value, operator: Operator, rhs: &Value) -> Option<Result<Value>> {
+        let handler = self
+            .handlers
+            .get(&(operator, lhs.kind.name(), rhs.kind.name()))?;
+
+        let span = lhs.span.merge(rhs.span);
+        let result = panic::catch_unwind(AssertUnwindSafe(|| handler(lhs, rhs)));
+
+        Some(match result {
+            Ok(result) => result.map(|value| Value::new(value.kind, span)),
+            Err(payload) => {
+                let message = payload
+                    .downcast_ref::<&str>()
+                    .map(|message| message.to_string())
+                    .or_else(|| payload.downcast_ref::<String>().cloned())
+                    .unwrap_or_else(|| "unknown panic".to_string());
+
+                Err(Error {
+                    span,
+                    kind: RuntimeError::HostFunctionPanicked(message).into(),
+                })
+            }
+        })
+    }
+}
+
+impl std::fmt::Debug for OperatorRegistry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_set().entries(self.handlers.keys()).finish()
+    }
+}
+
 impl ValueKind {
+    /// Returns the type of this value kind.
+    pub fn discriminant(&self) -> ValueType {
+        match self {
+            Self::Float(_) => ValueType::Float,
+            Self::Integer(_) => ValueType::Integer,
+            Self::Boolean(_) => ValueType::Boolean,
+            Self::String(_) => ValueType::String,
+            Self::Tuple(_) => ValueType::Tuple,
+            Self::Array(_) => ValueType::Array,
+            Self::Null => ValueType::Null,
+        }
+    }
+
     /// Returns the canonical name of this value kind.
+    pub fn name(&self) -> &'static str {
+        self.discriminant().name()
+    }
+}
+
+impl ValueType {
+    /// Returns the canonical name of this type.
     pub fn name(&self) -> &'static str {
         match self {
-            Self::Float(_) => "float",
-            Self::Integer(_) => "integer",
-            Self::Boolean(_) => "boolean",
-            Self::String(_) => "string",
+            Self::Float => "float",
+            Self::Integer => "integer",
+            Self::Boolean => "boolean",
+            Self::String => "string",
+            Self::Tuple => "tuple",
+            Self::Array => "array",
+            Self::Null => "null",
         }
     }
 }
@@ -201,10 +637,405 @@ impl Display for Value {
 impl Display for ValueKind {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.write_str(&match self {
+            Self::Float(f) if f.is_nan() => "nan".to_string(),
             Self::Float(f) => f.to_string(),
             Self::Integer(i) => i.to_string(),
             Self::Boolean(b) => b.to_string(),
             Self::String(s) => s.clone(),
+            Self::Null => "null".to_string(),
+            Self::Tuple(items) => match display_items(items).as_slice() {
+                [item] => format!("({item},)"),
+                items => format!("({})", items.join(", ")),
+            },
+            Self::Array(items) => format!("[{}]", display_items(items).join(", ")),
+        })
+    }
+}
+
+/// Displays the items of a collection, quoting any strings.
+fn display_items(items: &[Value]) -> Vec<String> {
+    items
+        .iter()
+        .map(|item| match &item.kind {
+            ValueKind::String(s) => format!("{s:?}"),
+            kind => kind.to_string(),
         })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::error::ErrorKind;
+
+    use super::*;
+
+    #[test]
+    fn test_checked_overflow() {
+        let error = Value::from(i64::MAX)
+            .add(&Value::from(1), ArithmeticMode::Checked)
+            .unwrap_err();
+
+        assert!(matches!(
+            error.kind,
+            ErrorKind::Runtime(RuntimeError::IntegerOverflow)
+        ));
+    }
+
+    #[test]
+    fn test_wrapping_overflow() {
+        let value = Value::from(i64::MAX)
+            .add(&Value::from(1), ArithmeticMode::Wrapping)
+            .unwrap();
+
+        assert_eq!(value.kind, ValueKind::Integer(i64::MIN));
+    }
+
+    #[test]
+    fn test_saturating_overflow() {
+        let value = Value::from(i64::MAX)
+            .add(&Value::from(1), ArithmeticMode::Saturating)
+            .unwrap();
+
+        assert_eq!(value.kind, ValueKind::Integer(i64::MAX));
+    }
+
+    #[test]
+    fn test_negate_overflow() {
+        let min = Value::from(i64::MIN);
+
+        let error = min.negate(ArithmeticMode::Checked).unwrap_err();
+        assert!(matches!(
+            error.kind,
+            ErrorKind::Runtime(RuntimeError::IntegerOverflow)
+        ));
+
+        let value = min.negate(ArithmeticMode::Wrapping).unwrap();
+        assert_eq!(value.kind, ValueKind::Integer(i64::MIN));
+
+        let value = min.negate(ArithmeticMode::Saturating).unwrap();
+        assert_eq!(value.kind, ValueKind::Integer(i64::MAX));
+    }
+
+    #[test]
+    fn test_value_conversions() {
+        assert_eq!(i64::try_from(Value::from(42)).unwrap(), 42);
+        assert_eq!(f64::try_from(Value::from(2.5)).unwrap(), 2.5);
+        assert!(bool::try_from(Value::from(true)).unwrap());
+        assert_eq!(
+            String::try_from(Value::from("hello".to_string())).unwrap(),
+            "hello"
+        );
+        assert_eq!(Value::from("hello"), Value::from("hello".to_string()));
+    }
+
+    #[test]
+    fn test_value_conversion_mismatch() {
+        let error = i64::try_from(Value::from(true)).unwrap_err();
+
+        assert!(matches!(
+            error.kind,
+            ErrorKind::Runtime(RuntimeError::UnexpectedValueKind {
+                expected: "integer",
+                found: ValueKind::Boolean(true),
+            })
+        ));
+    }
+
+    #[test]
+    fn test_coercion_matrix() {
+        use crate::token::Operator::{self, *};
+
+        let samples = [
+            Value::from(2),
+            Value::from(2.0),
+            Value::from(true),
+            Value::from("ab"),
+            Value::new(ValueKind::Tuple(vec![Value::from(1)]), Span::synthetic()),
+            Value::new(ValueKind::Array(vec![Value::from(1)]), Span::synthetic()),
+            Value::new(ValueKind::Null, Span::synthetic()),
+        ];
+
+        // (lhs, operator, rhs) => result kind, for every supported combination.
+        let supported: &[(&str, Operator, &str, &str)] = &[
+            ("string", Plus, "string", "string"),
+            ("integer", Multiply, "string", "string"),
+            ("boolean", And, "boolean", "boolean"),
+            ("boolean", Or, "boolean", "boolean"),
+        ];
+
+        // every arithmetic and comparison operator accepts any two numbers
+        let numeric: Vec<(&str, Operator, &str, &str)> = [
+            Plus,
+            Minus,
+            Multiply,
+            Divide,
+            Modulo,
+            Power,
+            LessThan,
+            LessThanEquals,
+            GreaterThan,
+            GreaterThanEquals,
+        ]
+        .into_iter()
+        .flat_map(|operator| {
+            let arithmetic = matches!(operator, Plus | Minus | Multiply | Divide | Modulo | Power);
+
+            [
+                ("integer", "integer", "integer"),
+                ("integer", "float", "float"),
+                ("float", "integer", "float"),
+                ("float", "float", "float"),
+            ]
+            .map(|(lhs, rhs, result)| {
+                let result = if arithmetic { result } else { "boolean" };
+                (lhs, operator, rhs, result)
+            })
+        })
+        .collect();
+
+        // any two values can be compared for equality
+        let equality: Vec<(&str, Operator, &str, &str)> = samples
+            .iter()
+            .flat_map(|lhs| {
+                samples
+                    .iter()
+                    .map(move |rhs| (lhs.kind.name(), rhs.kind.name()))
+            })
+            .flat_map(|(lhs, rhs)| {
+                [
+                    (lhs, Equals, rhs, "boolean"),
+                    (lhs, NotEquals, rhs, "boolean"),
+                ]
+            })
+            .collect();
+
+        let supported: Vec<_> = supported
+            .iter()
+            .copied()
+            .chain(numeric)
+            .chain(equality)
+            .collect();
+
+        let operators = [
+            Plus,
+            Minus,
+            Multiply,
+            Divide,
+            Modulo,
+            Power,
+            LessThan,
+            LessThanEquals,
+            GreaterThan,
+            GreaterThanEquals,
+            Equals,
+            NotEquals,
+            And,
+            Or,
+        ];
+
+        let apply = |lhs: &Value, operator: Operator, rhs: &Value| {
+            let mode = ArithmeticMode::default();
+
+            match operator {
+                Plus => lhs.add(rhs, mode),
+                Minus => lhs.subtract(rhs, mode),
+                Multiply => lhs.multiply(rhs, mode),
+                Divide => lhs.divide(rhs),
+                Modulo => lhs.modulo(rhs),
+                Power => lhs.power(rhs, mode),
+                LessThan => lhs.less_than(rhs),
+                LessThanEquals => lhs.less_than_or_equal(rhs),
+                GreaterThan => lhs.greater_than(rhs),
+                GreaterThanEquals => lhs.greater_than_or_equal(rhs),
+                Equals => lhs.equal(rhs),
+                NotEquals => lhs.not_equal(rhs),
+                And => lhs.and(rhs),
+                Or => lhs.or(rhs),
+                Not | Assign => unreachable!(),
+            }
+        };
+
+        for lhs in &samples {
+            for operator in operators {
+                for rhs in &samples {
+                    let expected = supported.iter().find(|(l, op, r, _)| {
+                        *l == lhs.kind.name() && *op == operator && *r == rhs.kind.name()
+                    });
+
+                    let result = apply(lhs, operator, rhs);
+
+                    match expected {
+                        Some((.., kind)) => assert_eq!(
+                            result.as_ref().map(|value| value.kind.name()).ok(),
+                            Some(*kind),
+                            "{} {operator} {}",
+                            lhs.kind.name(),
+                            rhs.kind.name()
+                        ),
+                        None => assert!(
+                            matches!(
+                                result,
+                                Err(Error {
+                                    kind: ErrorKind::Runtime(
+                                        RuntimeError::InvalidBinaryOperation { .. }
+                                    ),
+                                    ..
+                                })
+                            ),
+                            "{} {operator} {} should not be supported",
+                            lhs.kind.name(),
+                            rhs.kind.name()
+                        ),
+                    }
+                }
+            }
+        }
+
+        // supported kinds can still be outside an operator's domain
+        let result = apply(&Value::from(-1), Multiply, &Value::from("ab"));
+        assert!(matches!(
+            result,
+            Err(Error {
+                kind: ErrorKind::Runtime(RuntimeError::DomainError { .. }),
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn test_value_kind_discriminant() {
+        let cases = [
+            (ValueKind::Float(1.5), ValueType::Float),
+            (ValueKind::Integer(1), ValueType::Integer),
+            (ValueKind::Boolean(true), ValueType::Boolean),
+            (ValueKind::String("a".to_string()), ValueType::String),
+            (ValueKind::Tuple(vec![]), ValueType::Tuple),
+            (ValueKind::Array(vec![]), ValueType::Array),
+            (ValueKind::Null, ValueType::Null),
+        ];
+
+        for (kind, expected) in cases {
+            assert_eq!(kind.discriminant(), expected);
+            assert_eq!(kind.name(), expected.name());
+        }
+    }
+
+    #[test]
+    fn test_display_debug() {
+        assert_eq!(Value::from(42).display_debug(), "42 : integer");
+        assert_eq!(Value::from(2.5).display_debug(), "2.5 : float");
+        assert_eq!(Value::from(true).display_debug(), "true : boolean");
+        assert_eq!(Value::from("hi").display_debug(), "\"hi\" : string");
+    }
+
+    #[test]
+    fn test_mixed_arithmetic() {
+        let mode = ArithmeticMode::default();
+
+        let value = Value::from(1).add(&Value::from(2.5), mode).unwrap();
+        assert_eq!(value.kind, ValueKind::Float(3.5));
+
+        let value = Value::from(3.0).multiply(&Value::from(2), mode).unwrap();
+        assert_eq!(value.kind, ValueKind::Float(6.0));
+
+        let value = Value::from(5).divide(&Value::from(2.0)).unwrap();
+        assert_eq!(value.kind, ValueKind::Float(2.5));
+
+        let value = Value::from(2).less_than(&Value::from(2.5)).unwrap();
+        assert_eq!(value.kind, ValueKind::Boolean(true));
+    }
+
+    #[test]
+    fn test_division_by_zero() {
+        let error = Value::from(5).divide(&Value::from(0)).unwrap_err();
+        assert!(matches!(
+            error.kind,
+            ErrorKind::Runtime(RuntimeError::DivisionByZero)
+        ));
+
+        let value = Value::from(5.0).divide(&Value::from(0.0)).unwrap();
+        assert_eq!(value.kind, ValueKind::Float(f64::INFINITY));
+
+        let error = Value::from(i64::MIN).divide(&Value::from(-1)).unwrap_err();
+        assert!(matches!(
+            error.kind,
+            ErrorKind::Runtime(RuntimeError::IntegerOverflow)
+        ));
+    }
+
+    #[test]
+    fn test_power() {
+        use ArithmeticMode::*;
+
+        let power = |a: i64, b: i64, mode| Value::from(a).power(&Value::from(b), mode);
+
+        assert_eq!(
+            power(2, 10, Checked).unwrap().kind,
+            ValueKind::Integer(1024)
+        );
+        assert_eq!(power(2, -1, Checked).unwrap().kind, ValueKind::Float(0.5));
+
+        let error = power(10, 100, Checked).unwrap_err();
+        assert!(matches!(
+            error.kind,
+            ErrorKind::Runtime(RuntimeError::IntegerOverflow)
+        ));
+
+        // 0, 1 and -1 never overflow, even past `u32::MAX`
+        let huge = 5_000_000_001;
+        assert_eq!(power(1, huge, Checked).unwrap().kind, ValueKind::Integer(1));
+        assert_eq!(power(0, huge, Checked).unwrap().kind, ValueKind::Integer(0));
+        assert_eq!(
+            power(-1, huge, Checked).unwrap().kind,
+            ValueKind::Integer(-1)
+        );
+        assert_eq!(power(0, 0, Checked).unwrap().kind, ValueKind::Integer(1));
+
+        assert_eq!(power(2, 64, Wrapping).unwrap().kind, ValueKind::Integer(0));
+        assert_eq!(
+            power(3, 41, Wrapping).unwrap().kind,
+            ValueKind::Integer(3i64.wrapping_pow(41))
+        );
+        // odd numbers raised to 2^62 are 1 modulo 2^64
+        assert_eq!(
+            power(3, (1 << 62) + 5, Wrapping).unwrap().kind,
+            ValueKind::Integer(243)
+        );
+
+        assert_eq!(
+            power(2, 64, Saturating).unwrap().kind,
+            ValueKind::Integer(i64::MAX)
+        );
+        assert_eq!(
+            power(-3, huge, Saturating).unwrap().kind,
+            ValueKind::Integer(i64::MIN)
+        );
+    }
+
+    #[test]
+    fn test_equality_across_kinds() {
+        let equal = |a: Value, b: Value| a.equal(&b).unwrap().kind;
+        let not_equal = |a: Value, b: Value| a.not_equal(&b).unwrap().kind;
+
+        assert_eq!(equal(1.into(), true.into()), ValueKind::Boolean(false));
+        assert_eq!(not_equal(1.into(), "x".into()), ValueKind::Boolean(true));
+        assert_eq!(equal(1.into(), 1.0.into()), ValueKind::Boolean(true));
+        assert_eq!(equal("a".into(), "a".into()), ValueKind::Boolean(true));
+        assert_eq!(not_equal("a".into(), "b".into()), ValueKind::Boolean(true));
+
+        let tuple = |items: Vec<Value>| Value::new(ValueKind::Tuple(items), Span::synthetic());
+        let array = |items: Vec<Value>| Value::new(ValueKind::Array(items), Span::synthetic());
+
+        assert_eq!(
+            equal(
+                tuple(vec![1.into(), "a".into()]),
+                tuple(vec![1.0.into(), "a".into()])
+            ),
+            ValueKind::Boolean(true)
+        );
+        assert_eq!(
+            equal(tuple(vec![1.into()]), array(vec![1.into()])),
+            ValueKind::Boolean(false)
+        );
     }
 }